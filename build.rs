@@ -0,0 +1,55 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Embed the built player (`player/dist`, or `$SHELL_SCENE_PLAYER_DIST`) so
+/// `publish` works without the player project. Without a build, nothing is
+/// embedded and `publish` needs `--dist`.
+fn main() {
+    println!("cargo:rerun-if-env-changed=SHELL_SCENE_PLAYER_DIST");
+    let manifest = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let dist = env::var_os("SHELL_SCENE_PLAYER_DIST")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| manifest.join("player/dist"));
+    // without a dist, only the player's manifest: a missing path reruns this
+    // on every build, and all of `player` would mean scanning node_modules
+    let watched = if dist.is_dir() {
+        dist.clone()
+    } else {
+        manifest.join("player/package.json")
+    };
+    println!("cargo:rerun-if-changed={}", watched.display());
+
+    let mut files = Vec::new();
+    if dist.join("index.html").is_file() {
+        collect(&dist, &dist, &mut files);
+    }
+    files.sort();
+    let mut out = String::from("pub static PLAYER: &[(&str, &[u8])] = &[\n");
+    for (rel, path) in &files {
+        writeln!(out, "    ({rel:?}, include_bytes!({:?})),", path.display()).unwrap();
+    }
+    out.push_str("];\n");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("player.rs"), out).unwrap();
+}
+
+fn collect(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(root, &path, files);
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            let rel = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((rel, path));
+        }
+    }
+}
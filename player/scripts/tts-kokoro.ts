@@ -0,0 +1,31 @@
+// scripts/tts-kokoro.ts
+// Kokoro TTS for `shell-scene speech build`: reads one JSON request
+// ({ text, voice, out }) per line on stdin, writes a WAV to `out` and answers
+// "ok" (or the error) on a line of stdout.
+import readline from "node:readline";
+import { KokoroTTS } from "kokoro-js";
+
+const MODEL_ID = "onnx-community/Kokoro-82M-v1.0-ONNX";
+
+// stdout is the protocol; keep library chatter off it
+console.log = console.error;
+
+let tts: KokoroTTS | undefined;
+
+async function speak(line: string) {
+  const { text, voice, out } = JSON.parse(line);
+  tts ??= await KokoroTTS.from_pretrained(MODEL_ID, { dtype: "q8", device: "cpu" });
+  const audio = await tts.generate(text, { voice });
+  await audio.save(out);
+}
+
+const rl = readline.createInterface({ input: process.stdin });
+for await (const line of rl) {
+  if (!line.trim()) continue;
+  try {
+    await speak(line);
+    process.stdout.write("ok\n");
+  } catch (err) {
+    process.stdout.write(`${String((err as Error).message ?? err).replace(/\n/g, " ")}\n`);
+  }
+}
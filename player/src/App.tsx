@@ -9,7 +9,7 @@ import {CompositionWeb,  isTransition} from "./CompositionWeb";
 import type { TimelineItem } from "./CompositionWeb";
 import type {WebCompositionProps} from "./CompositionWeb";
 import {HashRouter as Router, Routes, Route, Link, Navigate, useParams} from "react-router-dom";
-import {getCastDurationSeconds} from "./getCastDuration";
+import {castItemSeconds} from "./getCastDuration";
 import {ChapterSidebar} from "./ChapterSidebar";
 import { useTimelineRegistry, getTimeline } from "./timelines/runtime";
 
@@ -164,7 +164,7 @@ function PlayerShell({ timeline }: { timeline: TimelineItem[] }) {
       const frames = await Promise.all(
         clipItems.map(async (it: any) =>
           it.type === "cast"
-            ? Math.max(1, Math.ceil((await getCastDurationSeconds(it.castPath)) * fps))
+            ? Math.max(1, Math.ceil((await castItemSeconds(it)) * fps))
             : Math.max(1, Math.ceil((it.seconds ?? 0) * fps))
         )
       );
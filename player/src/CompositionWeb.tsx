@@ -7,7 +7,13 @@ import {TitleCard} from "./TitleCard";
 export type Direction = "left" | "right" | "up" | "down";
 export type TransitionName = "cut" | "fade" | "crossfade" | "swipe" | "slide" | "wipe" | "blur";
 export type AudioAnchor = "inBegin" | "inEnd" | "outBegin" | "outEnd" | "absolute";
-export type SpeechCue = { text: string; voice: string; anchor: AudioAnchor; offsetSec?: number };
+export type SpeechCue = {
+  text: string;
+  voice: string;
+  anchor: AudioAnchor;
+  offsetSec?: number;
+  id?: string;            // names the voiceover file for `--tts files`
+};
 
 export type AudioAttachment = {
   src: string;
@@ -39,11 +45,18 @@ export type CardItem = {
   speech?: SpeechCue[];
 };
 
+export type CastMarker = { time: number; label: string };
+
 export type CastItem = {
   type: "cast";
   castPath: string;
   cols?: number;
   rows?: number;
+  // Filled in by `shell-scene build` from the cast file:
+  duration?: number;      // seconds
+  width?: number;         // recorded terminal size
+  height?: number;
+  markers?: CastMarker[];
   audio?: AudioAttachment | AudioAttachment[];
   speech?: SpeechCue[];
 };
@@ -19,3 +19,8 @@ export async function getCastDurationSeconds(url: string): Promise<number> {
   }
   return 0;
 }
+
+// Duration of a cast item: precomputed by `shell-scene build`, or read from the cast.
+export async function castItemSeconds(it: { castPath: string; duration?: number }): Promise<number> {
+  return typeof it.duration === "number" ? it.duration : getCastDurationSeconds(it.castPath);
+}
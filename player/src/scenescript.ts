@@ -104,18 +104,19 @@ export function parseSceneScript(input: string): Timeline {
 
     // Allow leading indentation then 'say "...'
     const m =
-      /^\s{2,}say\s+"([\s\S]*?)"(?:\s*@\s*([\d.]+)s?)?(?:\s+voice=([a-z0-9_]+))?(?:\s+anchor=(inBegin|inEnd|clipStart|baseEnd|visibleEnd))?\s*$/i.exec(
+      /^\s{2,}say\s+"([\s\S]*?)"(?:\s*@\s*([\d.]+)s?)?(?:\s+voice=([a-z0-9_]+))?(?:\s+anchor=(inBegin|inEnd|clipStart|baseEnd|visibleEnd))?(?:\s+id=([a-z0-9_-]+))?\s*$/i.exec(
         line
       );
     if (!m) return false;
 
-    const [, text, off, voice, anchor] = m;
+    const [, text, off, voice, anchor, id] = m;
 
     const sp: SpeechCue = {
       text,
       voice: voice ?? DEFAULT_VOICE,
       anchor: (anchor as AudioAnchor | undefined) ?? DEFAULT_ANCHOR,
       ...(off ? { offsetSec: Number(off) } : {}),
+      ...(id ? { id } : {}),
     };
     (current.speech ??= []).push(sp);
     return true;
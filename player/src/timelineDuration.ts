@@ -1,6 +1,6 @@
 // src/timelineDuration.ts
 import type { TimelineItem } from "./CompositionWeb";
-import { getCastDurationSeconds } from "./getCastDuration";
+import { castItemSeconds } from "./getCastDuration";
 
 export async function timelineFrames(
   items: TimelineItem[],
@@ -18,8 +18,8 @@ export async function timelineFrames(
       continue;
     }
     if (it.type === "cast") {
-      // Use your existing helper to read .cast duration (in seconds)
-      const sec = await getCastDurationSeconds(it.castPath);
+      // Precomputed by the build when available, else read from the .cast
+      const sec = await castItemSeconds(it);
       frames += Math.round(sec * fps);
       continue;
     }
@@ -1,7 +1,11 @@
+use crate::util::config::{CursorStyle, Renderer};
+use crate::util::recorder::Recorder;
+use crate::util::speech::{Backend, Format};
 use clap::builder::BoolishValueParser;
-use clap::{value_parser, Arg, ArgAction, Command};
+use clap::{Arg, ArgAction, Command, value_parser};
 use clap_complete::shells::Shell;
 use std::io;
+use std::net::IpAddr;
 use std::path::PathBuf;
 
 fn leak_str(s: &str) -> &'static str {
@@ -10,7 +14,7 @@ fn leak_str(s: &str) -> &'static str {
 }
 
 pub fn app(binary_name: &str) -> Command {
-    Command::new(leak_str(binary_name))
+    let app = Command::new(leak_str(binary_name))
         .version(env!("CARGO_PKG_VERSION"))
         .author(env!("CARGO_PKG_AUTHORS"))
         .about(env!("CARGO_PKG_DESCRIPTION"))
@@ -35,6 +39,15 @@ pub fn app(binary_name: &str) -> Command {
         .subcommand(
             Command::new("record")
                 .about("Record an asciicast via ttyd")
+                .arg(
+                    Arg::new("config")
+                        .long("config")
+                        .num_args(1)
+                        .value_name("PATH")
+                        .env("SHELL_SCENE_CONFIG")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Config file. Default: ~/.config/shell-scene/config.toml"),
+                )
                 .arg(
                     Arg::new("session")
                         .long("session")
@@ -81,8 +94,128 @@ pub fn app(binary_name: &str) -> Command {
                         .value_name("PT")
                         .env("FONT_SIZE")
                         .value_parser(value_parser!(u32))
-                        .default_value("24")
-                        .help("font size for ttyd"),
+                        .help("font size for ttyd. Default: 24"),
+                )
+                .arg(
+                    Arg::new("font_family")
+                        .long("font-family")
+                        .num_args(1)
+                        .value_name("FONT")
+                        .env("FONT_FAMILY")
+                        .help("font family for ttyd (CSS font-family)"),
+                )
+                .arg(
+                    Arg::new("line_height")
+                        .long("line-height")
+                        .num_args(1)
+                        .value_name("N")
+                        .env("LINE_HEIGHT")
+                        .value_parser(value_parser!(f64))
+                        .help("line height for ttyd, relative to the font size"),
+                )
+                .arg(
+                    Arg::new("cursor_style")
+                        .long("cursor-style")
+                        .num_args(1)
+                        .value_name("STYLE")
+                        .env("CURSOR_STYLE")
+                        .value_parser(value_parser!(CursorStyle))
+                        .help("cursor style for ttyd"),
+                )
+                .arg(
+                    Arg::new("cursor_blink")
+                        .long("cursor-blink")
+                        .env("CURSOR_BLINK")
+                        .num_args(0..=1)
+                        .default_missing_value("true")
+                        .value_parser(BoolishValueParser::new())
+                        .action(ArgAction::Set)
+                        .help("blink the cursor in ttyd (supports true/false/1/0/yes/no/on/off)"),
+                )
+                .arg(
+                    Arg::new("renderer")
+                        .long("renderer")
+                        .num_args(1)
+                        .value_name("TYPE")
+                        .env("TT_RENDERER")
+                        .value_parser(value_parser!(Renderer))
+                        .help("xterm.js renderer for ttyd"),
+                )
+                .arg(
+                    Arg::new("theme")
+                        .long("theme")
+                        .num_args(1)
+                        .value_name("NAME|FILE")
+                        .env("TT_THEME")
+                        .help("ttyd colors: asciinema (as in the player), xterm, or a JSON file of xterm.js theme colors"),
+                )
+                .arg(
+                    Arg::new("server")
+                        .long("server")
+                        .num_args(1)
+                        .value_name("SERVER")
+                        .env("TT_SERVER")
+                        .value_parser(["ttyd", "builtin"])
+                        .help("web terminal server. Default: ttyd if installed, else the built-in one (if compiled with the web-terminal feature)"),
+                )
+                .arg(
+                    Arg::new("browser")
+                        .long("browser")
+                        .num_args(1)
+                        .value_name("CMD")
+                        .env("SHELL_SCENE_BROWSER")
+                        .help("browser command to open the recording UI with. Default: xdg-open"),
+                )
+                .arg(
+                    Arg::new("no_open")
+                        .long("no-open")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("app_window")
+                        .help("Don't open the recording UI in a browser"),
+                )
+                .arg(
+                    Arg::new("app_window")
+                        .long("app-window")
+                        .action(ArgAction::SetTrue)
+                        .help("Open the recording UI in its own browser window sized to cols x rows (Chromium app mode or Firefox)"),
+                )
+                .arg(
+                    Arg::new("bind")
+                        .long("bind")
+                        .num_args(1)
+                        .value_name("ADDR")
+                        .env("TT_BIND")
+                        .value_parser(value_parser!(IpAddr))
+                        .default_value("127.0.0.1")
+                        .help("address ttyd listens on (e.g. 0.0.0.0 to record from another machine)"),
+                )
+                .arg(
+                    Arg::new("credential")
+                        .long("credential")
+                        .num_args(1)
+                        .value_name("USER:PASSWORD")
+                        .env("TT_CREDENTIAL")
+                        .help("require HTTP basic auth for ttyd"),
+                )
+                .arg(
+                    Arg::new("tls_cert")
+                        .long("tls-cert")
+                        .num_args(1)
+                        .value_name("PATH")
+                        .env("TT_TLS_CERT")
+                        .value_parser(value_parser!(PathBuf))
+                        .requires("tls_key")
+                        .help("serve ttyd over HTTPS with this certificate (PEM)"),
+                )
+                .arg(
+                    Arg::new("tls_key")
+                        .long("tls-key")
+                        .num_args(1)
+                        .value_name("PATH")
+                        .env("TT_TLS_KEY")
+                        .value_parser(value_parser!(PathBuf))
+                        .requires("tls_cert")
+                        .help("private key (PEM) for --tls-cert"),
                 )
                 .arg(
                     Arg::new("out")
@@ -116,12 +249,99 @@ pub fn app(binary_name: &str) -> Command {
                     // store the parsed bool
                         .action(ArgAction::Set)
                         .help("Kill tmux session after detach (supports true/false/1/0/yes/no/on/off)"),
+                )
+                .arg(
+                    Arg::new("append")
+                        .long("append")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("take")
+                        .help("Append to the cast (default: the session's latest cast) instead of overwriting it"),
+                )
+                .arg(
+                    Arg::new("take")
+                        .long("take")
+                        .num_args(1)
+                        .value_name("N")
+                        .value_parser(value_parser!(u32).range(1..))
+                        .help("Record take N as NAME.takeN.cast next to the cast (default: the session's latest cast)"),
+                )
+                .arg(
+                    Arg::new("clear")
+                        .long("clear")
+                        .action(ArgAction::SetTrue)
+                        .help("Clear the screen and scrollback of a staged session before recording"),
+                )
+                .arg(recorder_arg())
+                .arg(ssh_arg())
+                .arg(ssh_command_arg())
+                .arg(container_arg())
+                .arg(container_runtime_arg())
+                .args_conflicts_with_subcommands(true)
+                .subcommand(
+                    Command::new("pause")
+                        .about("Pause a running recording (tmux: prefix P)")
+                        .arg(session_arg()),
+                )
+                .subcommand(
+                    Command::new("resume")
+                        .about("Resume a paused recording (tmux: prefix R)")
+                        .arg(session_arg()),
+                ),
+        )
+        // --- stage ---
+        .subcommand(
+            Command::new("stage")
+                .about("Prepare a recording session off-camera")
+                .arg(session_arg())
+                .arg(
+                    Arg::new("cols")
+                        .long("cols")
+                        .num_args(1)
+                        .value_name("N")
+                        .env("TMUX_COLS")
+                        .value_parser(value_parser!(u32))
+                        .default_value("80")
+                        .help("tmux cols"),
+                )
+                .arg(
+                    Arg::new("rows")
+                        .long("rows")
+                        .num_args(1)
+                        .value_name("N")
+                        .env("TMUX_ROWS")
+                        .value_parser(value_parser!(u32))
+                        .default_value("24")
+                        .help("tmux rows"),
+                )
+                .arg(
+                    Arg::new("workdir")
+                        .long("workdir")
+                        .num_args(1)
+                        .value_name("PATH")
+                        .env("WORKING_DIRECTORY")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("working directory for tmux session. Default: $HOME"),
+                )
+                .arg(
+                    Arg::new("run")
+                        .long("run")
+                        .num_args(1)
+                        .value_name("CMD")
+                        .action(ArgAction::Append)
+                        .help("Type CMD into the session (repeatable)"),
+                )
+                .arg(
+                    Arg::new("detach")
+                        .long("detach")
+                        .short('d')
+                        .action(ArgAction::SetTrue)
+                        .help("Don't attach to the session"),
                 ),
         )
         // --- record-hook (internal) ---
         .subcommand(
             Command::new("record-hook")
-                .about("INTERNAL: tmux/asciinema worker invoked inside ttyd")
+                .about("INTERNAL: tmux/recorder worker invoked inside ttyd")
                 .hide(true)
                 .arg(
                     Arg::new("child")
@@ -177,8 +397,357 @@ pub fn app(binary_name: &str) -> Command {
                         .long("kill-on-detach")
                         .env("TMUX_KILL_ON_DETACH")
                         .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("append")
+                        .long("append")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("clear")
+                        .long("clear")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(recorder_arg())
+                .arg(ssh_arg())
+                .arg(ssh_command_arg())
+                .arg(container_arg())
+                .arg(container_runtime_arg()),
+        )
+        // --- casts ---
+        .subcommand(
+            Command::new("casts")
+                .about("Manage the library of recorded casts")
+                .visible_alias("cast")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("list")
+                        .about("List recorded casts")
+                        .arg(cast_dir_arg())
+                        .arg(player_arg())
+                        .arg(player_timelines_arg())
+                        .arg(
+                            Arg::new("session")
+                                .long("session")
+                                .num_args(1)
+                                .value_name("NAME")
+                                .help("Only show casts recorded for this session"),
+                        )
+                        .arg(
+                            Arg::new("referenced")
+                                .long("referenced")
+                                .action(ArgAction::SetTrue)
+                                .conflicts_with("unreferenced")
+                                .help("Only show casts referenced by a timeline"),
+                        )
+                        .arg(
+                            Arg::new("unreferenced")
+                                .long("unreferenced")
+                                .action(ArgAction::SetTrue)
+                                .help("Only show casts not referenced by any timeline"),
+                        )
+                        .arg(
+                            Arg::new("sort")
+                                .long("sort")
+                                .num_args(1)
+                                .value_name("KEY")
+                                .value_parser(["date", "name", "size", "duration"])
+                                .default_value("date")
+                                .help("Sort order"),
+                        )
+                        .arg(
+                            Arg::new("reverse")
+                                .long("reverse")
+                                .short('r')
+                                .action(ArgAction::SetTrue)
+                                .help("Reverse the sort order"),
+                        )
+                        .arg(
+                            Arg::new("json")
+                                .long("json")
+                                .action(ArgAction::SetTrue)
+                                .help("Print the list as JSON"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("prune")
+                        .about("Trash casts that no timeline references")
+                        .arg(cast_dir_arg())
+                        .arg(player_arg())
+                        .arg(player_timelines_arg())
+                        .arg(
+                            Arg::new("older_than")
+                                .long("older-than")
+                                .num_args(1)
+                                .value_name("AGE")
+                                .default_value("7d")
+                                .help("Only prune casts older than AGE (e.g. 30m, 12h, 7d, 2w)"),
+                        )
+                        .arg(
+                            Arg::new("trash")
+                                .long("trash")
+                                .num_args(1)
+                                .value_name("PATH")
+                                .value_parser(value_parser!(PathBuf))
+                                .help("trash directory. Default: <cast dir>/.trash"),
+                        )
+                        .arg(
+                            Arg::new("yes")
+                                .long("yes")
+                                .short('y')
+                                .action(ArgAction::SetTrue)
+                                .help("Move the listed casts to the trash instead of only listing them"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("check")
+                        .about("Check casts for truncation, bad timestamps and broken headers")
+                        .arg(cast_dir_arg())
+                        .arg(
+                            Arg::new("files")
+                                .num_args(1..)
+                                .value_name("FILE")
+                                .value_parser(value_parser!(PathBuf))
+                                .help("casts to check. Default: every cast in the cast directory"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("repair")
+                        .about("Rewrite broken casts so they play back (original kept as .orig)")
+                        .arg(
+                            Arg::new("files")
+                                .num_args(1..)
+                                .required(true)
+                                .value_name("FILE")
+                                .value_parser(value_parser!(PathBuf))
+                                .help("casts to repair"),
+                        )
+                        .arg(
+                            Arg::new("out")
+                                .long("out")
+                                .num_args(1)
+                                .value_name("PATH")
+                                .value_parser(value_parser!(PathBuf))
+                                .help("write the repaired cast here instead of in place"),
+                        )
+                        .arg(
+                            Arg::new("cols")
+                                .long("cols")
+                                .num_args(1)
+                                .value_name("N")
+                                .env("TMUX_COLS")
+                                .value_parser(value_parser!(u32))
+                                .default_value("80")
+                                .help("width to use if the header has none"),
+                        )
+                        .arg(
+                            Arg::new("rows")
+                                .long("rows")
+                                .num_args(1)
+                                .value_name("N")
+                                .env("TMUX_ROWS")
+                                .value_parser(value_parser!(u32))
+                                .default_value("24")
+                                .help("height to use if the header has none"),
+                        ),
+                ),
+        )
+        // --- sessions ---
+        .subcommand(
+            Command::new("sessions")
+                .about("Manage the tmux sessions used for recording")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(Command::new("list").about("List recording sessions"))
+                .subcommand(
+                    Command::new("attach")
+                        .about("Attach to a recording session in this terminal")
+                        .arg(session_arg()),
+                )
+                .subcommand(
+                    Command::new("kill")
+                        .about("Kill a recording session")
+                        .arg(session_arg())
+                        .arg(force_arg()),
+                )
+                .subcommand(
+                    Command::new("kill-all")
+                        .about("Kill every recording session")
+                        .arg(force_arg()),
                 ),
         )
+        // --- build ---
+        .subcommand(
+            Command::new("build")
+                .about("Compile .timeline files for the player")
+                .arg(player_arg())
+                .arg(player_timelines_arg())
+                .arg(cast_dir_arg())
+                .args(speech_args())
+                .arg(no_speech_arg()),
+        )
+        // --- speech ---
+        .subcommand(
+            Command::new("speech")
+                .about("Synthesize the speech of compiled timelines")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("build")
+                        .about("Turn `say` cues into audio, reusing audio already synthesized")
+                        .arg(
+                            Arg::new("names")
+                                .num_args(0..)
+                                .value_name("TIMELINE")
+                                .help("timelines to synthesize. Default: all compiled"),
+                        )
+                        .arg(player_arg())
+                        .args(speech_args()),
+                )
+                .subcommand(
+                    Command::new("say")
+                        .about("Show how the pronunciation dictionary has text said")
+                        .arg(Arg::new("text").required(true).value_name("TEXT"))
+                        .arg(
+                            Arg::new("voice")
+                                .long("voice")
+                                .num_args(1)
+                                .value_name("VOICE")
+                                .default_value("af_heart")
+                                .help("voice whose rules apply"),
+                        )
+                        .arg(player_arg())
+                        .arg(pronunciation_arg()),
+                )
+                .subcommand(
+                    Command::new("status")
+                        .about("List the cues of every timeline with cached or missing audio")
+                        .arg(player_arg())
+                        .arg(player_timelines_arg())
+                        .args(speech_args()),
+                )
+                .subcommand(
+                    Command::new("gc")
+                        .about("Delete synthesized audio that no timeline uses anymore")
+                        .after_help(
+                            "Audio is kept if a compiled timeline plays it or if it is the audio \
+                             of a `say` line for the given --tts, --format and --pronunciation; \
+                             audio made with other options is deleted.",
+                        )
+                        .arg(player_arg())
+                        .arg(player_timelines_arg())
+                        .args(speech_args())
+                        .arg(
+                            Arg::new("yes")
+                                .long("yes")
+                                .short('y')
+                                .action(ArgAction::SetTrue)
+                                .help("Delete the listed files instead of only listing them"),
+                        ),
+                ),
+        )
+        // --- publish ---
+        .subcommand(
+            Command::new("publish")
+                .about("Export presentations as a static site")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .num_args(1)
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("directory to write the site to (the .html file with --single-file)"),
+                )
+                .arg(
+                    Arg::new("names")
+                        .num_args(0..)
+                        .value_name("TIMELINE")
+                        .help("timelines to publish. Default: all"),
+                )
+                .arg(
+                    Arg::new("single_file")
+                        .long("single-file")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("base")
+                        .help("Write one .html file with everything inlined, to open offline"),
+                )
+                .arg(player_arg())
+                .arg(dist_arg().help(
+                    "built player to publish instead of the one embedded in shell-scene",
+                ))
+                .arg(cast_dir_arg())
+                .arg(
+                    Arg::new("base")
+                        .long("base")
+                        .num_args(1)
+                        .value_name("URL")
+                        .env("PUBLISH_BASE")
+                        .help("URL the site will be served from (e.g. /talks/). Default: relative"),
+                )
+                .arg(force_arg().help("Write into DIR even if it is not empty, or replace the .html file")),
+        )
+        // --- serve ---
+        .subcommand(
+            Command::new("serve")
+                .about("Preview presentations in a browser")
+                .arg(player_arg())
+                .arg(dist_arg().help("built player. Default: PLAYER/dist"))
+                .arg(cast_dir_arg())
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .num_args(1)
+                        .value_name("PORT")
+                        .env("SERVE_PORT")
+                        .value_parser(value_parser!(u16))
+                        .default_value("8000")
+                        .help("starting port"),
+                )
+                .arg(
+                    Arg::new("bind")
+                        .long("bind")
+                        .num_args(1)
+                        .value_name("ADDR")
+                        .env("SERVE_BIND")
+                        .value_parser(value_parser!(IpAddr))
+                        .default_value("127.0.0.1")
+                        .help("address to listen on"),
+                )
+                .arg(
+                    Arg::new("browser")
+                        .long("browser")
+                        .num_args(1)
+                        .value_name("CMD")
+                        .env("SHELL_SCENE_BROWSER")
+                        .help("browser command to open the preview with. Default: xdg-open"),
+                )
+                .arg(
+                    Arg::new("no_open")
+                        .long("no-open")
+                        .action(ArgAction::SetTrue)
+                        .help("Don't open the preview in a browser"),
+                )
+                .arg(player_timelines_arg())
+                .arg(
+                    Arg::new("rebuild")
+                        .long("rebuild")
+                        .num_args(1)
+                        .value_name("CMD")
+                        .env("SERVE_REBUILD")
+                        .help("command (run in PLAYER) that recompiles changed timelines, instead of `build`; empty to skip"),
+                )
+                .arg(
+                    Arg::new("no_reload")
+                        .long("no-reload")
+                        .action(ArgAction::SetTrue)
+                        .help("Don't watch for changes and reload open players"),
+                )
+                .args(speech_args())
+                .arg(no_speech_arg().help("Don't synthesize speech when rebuilding")),
+        )
         // --- completions ---
         .subcommand(
             Command::new("completions")
@@ -189,7 +758,227 @@ pub fn app(binary_name: &str) -> Command {
                         .required(false)
                         .value_parser(["bash", "zsh", "fish"]),
                 ),
+        );
+    #[cfg(feature = "web-terminal")]
+    let app = app.subcommand(web_terminal_command());
+    app
+}
+
+// --- web-terminal (internal) ---
+#[cfg(feature = "web-terminal")]
+fn web_terminal_command() -> Command {
+    Command::new("web-terminal")
+        .about("INTERNAL: built-in ttyd replacement spawned by record")
+        .hide(true)
+        .arg(
+            Arg::new("port")
+                .short('p')
+                .long("port")
+                .num_args(1)
+                .value_parser(value_parser!(u16))
+                .default_value("7681"),
+        )
+        .arg(
+            Arg::new("interface")
+                .short('i')
+                .long("interface")
+                .num_args(1)
+                .value_parser(value_parser!(IpAddr))
+                .default_value("127.0.0.1"),
+        )
+        .arg(
+            Arg::new("credential")
+                .short('c')
+                .long("credential")
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("client_option")
+                .short('t')
+                .long("client-option")
+                .num_args(1)
+                .action(ArgAction::Append),
         )
+        .arg(
+            Arg::new("once")
+                .short('o')
+                .long("once")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("writable")
+                .short('W')
+                .long("writable")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("command")
+                .num_args(1..)
+                .required(true)
+                .trailing_var_arg(true)
+                .allow_hyphen_values(true),
+        )
+}
+
+fn session_arg() -> Arg {
+    Arg::new("session")
+        .long("session")
+        .num_args(1)
+        .value_name("NAME")
+        .env("SESSION")
+        .default_value("cast")
+        .help("tmux session name")
+}
+
+fn ssh_arg() -> Arg {
+    Arg::new("ssh")
+        .long("ssh")
+        .num_args(1)
+        .value_name("USER@HOST")
+        .env("SHELL_SCENE_SSH_HOST")
+        .help("Run the tmux session on a remote host over ssh (--workdir is a remote path)")
+}
+
+fn ssh_command_arg() -> Arg {
+    Arg::new("ssh_command")
+        .long("ssh-command")
+        .num_args(1)
+        .value_name("CMD")
+        .env("SSH_COMMAND")
+        .default_value("ssh")
+        .help("ssh client used for --ssh")
+}
+
+fn container_arg() -> Arg {
+    Arg::new("container")
+        .long("container")
+        .num_args(1)
+        .value_name("IMAGE")
+        .env("CONTAINER_IMAGE")
+        .help("Run the shell in a disposable container of IMAGE, with --workdir mounted at /work")
+}
+
+fn container_runtime_arg() -> Arg {
+    Arg::new("container_runtime")
+        .long("container-runtime")
+        .num_args(1)
+        .value_name("CMD")
+        .env("CONTAINER_RUNTIME")
+        .help("podman/docker compatible CLI used for --container. Default: podman, else docker")
+}
+
+fn recorder_arg() -> Arg {
+    Arg::new("recorder")
+        .long("recorder")
+        .num_args(1)
+        .value_name("RECORDER")
+        .env("RECORDER")
+        .value_parser(value_parser!(Recorder))
+        .default_value("asciinema")
+        .help("What writes the cast: asciinema or the built-in recorder")
+}
+
+fn force_arg() -> Arg {
+    Arg::new("force")
+        .long("force")
+        .short('f')
+        .action(ArgAction::SetTrue)
+        .help("Also kill sessions that are recording")
+}
+
+fn cast_dir_arg() -> Arg {
+    Arg::new("dir")
+        .long("dir")
+        .num_args(1)
+        .value_name("PATH")
+        .env("CASTS_DIR")
+        .value_parser(value_parser!(PathBuf))
+        .help("cast directory. Default: $HOME/casts")
+}
+
+fn player_arg() -> Arg {
+    Arg::new("player")
+        .long("player")
+        .num_args(1)
+        .value_name("PATH")
+        .env("PLAYER_DIR")
+        .value_parser(value_parser!(PathBuf))
+        .default_value("player")
+        .help("player project (with public/timelines and public/speech)")
+}
+
+fn dist_arg() -> Arg {
+    Arg::new("dist")
+        .long("dist")
+        .num_args(1)
+        .value_name("PATH")
+        .env("PLAYER_DIST")
+        .value_parser(value_parser!(PathBuf))
+}
+
+/// `.timeline` sources of a player project.
+fn player_timelines_arg() -> Arg {
+    Arg::new("timelines")
+        .long("timelines")
+        .num_args(1)
+        .value_name("PATH")
+        .env("TIMELINES_DIR")
+        .value_parser(value_parser!(PathBuf))
+        .help("directory searched (recursively) for .timeline files. Default: PLAYER/timelines")
+}
+
+/// How the speech step synthesizes and encodes speech.
+fn speech_args() -> [Arg; 5] {
+    [
+        Arg::new("tts")
+            .long("tts")
+            .num_args(1)
+            .value_name("BACKEND")
+            .env("SPEECH_TTS")
+            .value_parser(value_parser!(Backend))
+            .default_value("kokoro")
+            .help("text-to-speech backend"),
+        Arg::new("tts_command")
+            .long("tts-command")
+            .num_args(1)
+            .value_name("CMD")
+            .env("SPEECH_TTS_COMMAND")
+            .required_if_eq("tts", "command")
+            .help("for --tts command: shell command (run in PLAYER) given the cue as JSON (text, voice, timeline, id) on stdin and in $SPEECH_TEXT/$SPEECH_VOICE, writing a WAV to stdout"),
+        Arg::new("voiceover")
+            .long("voiceover")
+            .num_args(1)
+            .value_name("PATH")
+            .env("VOICEOVER_DIR")
+            .value_parser(value_parser!(PathBuf))
+            .help("for --tts files: recordings, as TIMELINE/ID.wav. Default: PLAYER/voiceover"),
+        pronunciation_arg(),
+        Arg::new("format")
+            .long("format")
+            .num_args(1)
+            .value_name("FORMAT")
+            .env("SPEECH_FORMAT")
+            .value_parser(value_parser!(Format))
+            .default_value("opus")
+            .help("audio format of the speech (opus and vorbis are encoded with ffmpeg)"),
+    ]
+}
+
+fn pronunciation_arg() -> Arg {
+    Arg::new("pronunciation")
+        .long("pronunciation")
+        .num_args(1)
+        .value_name("PATH")
+        .env("PRONUNCIATION_FILE")
+        .value_parser(value_parser!(PathBuf))
+        .help("pronunciation dictionary. Default: PLAYER/pronunciation.toml")
+}
+
+fn no_speech_arg() -> Arg {
+    Arg::new("no_speech")
+        .long("no-speech")
+        .action(ArgAction::SetTrue)
+        .help("Only compile; leave `say` cues for `speech build`")
 }
 
 pub fn generate_completion_script(shell: Shell, binary_name: &str) {
@@ -242,4 +1031,28 @@ mod tests {
         let kill = *sub.1.get_one::<bool>("kill_on_detach").unwrap_or(&true);
         assert!(!kill, "explicit =0 should parse as false");
     }
+
+    #[test]
+    fn record_pause_takes_session() {
+        let m = test_cmd()
+            .try_get_matches_from(["shell-scene", "record", "pause", "--session", "demo"])
+            .expect("parse should succeed");
+        let (_, record) = m.subcommand().expect("has sub");
+        let (name, pause) = record.subcommand().expect("has pause");
+        assert_eq!(name, "pause");
+        assert_eq!(pause.get_one::<String>("session").unwrap(), "demo");
+    }
+
+    #[test]
+    fn cast_alias_parses_casts_list() {
+        let m = test_cmd()
+            .try_get_matches_from(["shell-scene", "cast", "list", "--json", "--sort", "size"])
+            .expect("parse should succeed");
+        let (name, sub) = m.subcommand().expect("has sub");
+        assert_eq!(name, "casts");
+        let (name, list) = sub.subcommand().expect("has list");
+        assert_eq!(name, "list");
+        assert!(list.get_flag("json"));
+        assert_eq!(list.get_one::<String>("sort").unwrap(), "size");
+    }
 }
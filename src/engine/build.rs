@@ -0,0 +1,67 @@
+use crate::engine::speech;
+use crate::util::compile::{self, Project};
+use crate::util::speech::Options;
+use crate::util::{eprintln_err, fsx};
+use clap::ArgMatches;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// The project named by `--player`, `--timelines` and `--dir`.
+pub fn project(m: &ArgMatches) -> Project {
+    let player = m.get_one::<PathBuf>("player").unwrap().clone();
+    Project {
+        timelines: path_arg(m, "timelines").unwrap_or_else(|| player.join("timelines")),
+        casts: path_arg(m, "dir").unwrap_or_else(fsx::default_cast_dir),
+        player,
+    }
+}
+
+/// A path option commands that don't take it leave unset.
+fn path_arg(m: &ArgMatches, id: &str) -> Option<PathBuf> {
+    m.try_get_one::<PathBuf>(id).ok().flatten().cloned()
+}
+
+/// `build`: compile the project's timelines into the player's `public/`.
+pub fn run_build(m: &ArgMatches) -> i32 {
+    let project = project(m);
+    if !project.timelines.is_dir() {
+        eprintln_err(&format!("Not found: {}", project.timelines.display()));
+        return 1;
+    }
+    if build(&project, speech::options(m).as_ref(), None) {
+        0
+    } else {
+        1
+    }
+}
+
+/// Run a build (of the timeline files `only`, or all), reporting what it
+/// did, then synthesize the speech of what it wrote unless `speech` is
+/// `None`. False if anything failed.
+pub fn build(
+    project: &Project,
+    speech: Option<&Options>,
+    only: Option<&BTreeSet<PathBuf>>,
+) -> bool {
+    let built = match compile::build(project, only) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln_err(&format!("Build failed: {e}"));
+            return false;
+        }
+    };
+    for p in &built.problems {
+        eprintln_err(p);
+    }
+    eprintln!(
+        "[build] Wrote {} timeline(s) using {} cast(s) to {}",
+        built.names.len(),
+        built.casts,
+        project.compiled().display()
+    );
+    let spoken = match speech {
+        Some(options) => speech::synthesize(project, &built.names, options),
+        None => true,
+    };
+    built.problems.is_empty() && spoken
+}
@@ -0,0 +1,516 @@
+use crate::engine::build;
+use crate::util::compile::Project;
+use crate::util::human::{human_duration, human_size};
+use crate::util::{cast, eprintln_err, fsx, timeline};
+use chrono::{DateTime, Duration, Local, NaiveDateTime};
+use clap::ArgMatches;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CastEntry {
+    pub path: PathBuf,
+    pub session: String,
+    #[serde(serialize_with = "ser_datetime")]
+    pub recorded_at: Option<NaiveDateTime>,
+    pub size: u64,
+    pub duration: f64,
+    pub referenced_by: Vec<PathBuf>,
+}
+
+fn ser_datetime<S: serde::Serializer>(v: &Option<NaiveDateTime>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(t) => s.serialize_str(&t.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        None => s.serialize_none(),
+    }
+}
+
+pub fn run_casts(m: &ArgMatches) -> i32 {
+    match m.subcommand() {
+        Some(("list", m)) => run_list(m),
+        Some(("prune", m)) => run_prune(m),
+        Some(("check", m)) => run_check(m),
+        Some(("repair", m)) => run_repair(m),
+        _ => 1,
+    }
+}
+
+/// Split `{session}-{YYYYmmdd-HHMMSS}` (as written by `record`) into its parts.
+/// A `.takeN` suffix is ignored.
+pub fn parse_cast_stem(stem: &str) -> (String, Option<NaiveDateTime>) {
+    let (stem, _) = fsx::split_take(stem);
+    const TS_LEN: usize = "YYYYmmdd-HHMMSS".len();
+    if stem.len() > TS_LEN + 1 && stem.is_char_boundary(stem.len() - TS_LEN - 1) {
+        let (session, ts) = stem.split_at(stem.len() - TS_LEN - 1);
+        if ts.starts_with('-')
+            && let Ok(t) = NaiveDateTime::parse_from_str(&ts[1..], fsx::CAST_TIMESTAMP_FORMAT)
+        {
+            return (session.to_string(), Some(t));
+        }
+    }
+    (stem.to_string(), None)
+}
+
+/// Most recent first take of `session` in `dir`.
+pub fn latest_session_cast(dir: &Path, session: &str) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "cast"))
+        .filter_map(|p| {
+            let stem = p.file_stem()?.to_string_lossy().into_owned();
+            if fsx::split_take(&stem).1.is_some() {
+                return None;
+            }
+            match parse_cast_stem(&stem) {
+                (s, Some(t)) if s == session => Some((t, p)),
+                _ => None,
+            }
+        })
+        .max()
+        .map(|(_, p)| p)
+}
+
+/// Scan `dir` for `.cast` files and cross-reference them with `timelines_dir`.
+pub fn scan(dir: &Path, timelines_dir: &Path) -> Vec<CastEntry> {
+    let refs = timeline::cast_refs(timelines_dir);
+    let Ok(rd) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for entry in rd.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_none_or(|e| e != "cast") {
+            continue;
+        }
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let (session, mut recorded_at) = parse_cast_stem(&stem);
+        let info = cast::read_info(&path).unwrap_or_default();
+        if recorded_at.is_none() {
+            recorded_at = info
+                .timestamp
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .map(|d| d.with_timezone(&Local).naive_local());
+        }
+        let mut referenced_by: Vec<PathBuf> = refs
+            .iter()
+            .filter(|r| timeline::refers_to(r, &path))
+            .map(|r| r.timeline.clone())
+            .collect();
+        referenced_by.dedup();
+        out.push(CastEntry {
+            size: entry.metadata().map(|md| md.len()).unwrap_or(0),
+            duration: info.duration,
+            path,
+            session,
+            recorded_at,
+            referenced_by,
+        });
+    }
+    out
+}
+
+fn run_list(m: &ArgMatches) -> i32 {
+    let Project {
+        casts: dir,
+        timelines: timelines_dir,
+        ..
+    } = build::project(m);
+    let session = m.get_one::<String>("session");
+    let sort = m.get_one::<String>("sort").unwrap();
+
+    if !dir.is_dir() {
+        eprintln_err(&format!("Cast directory does not exist: {}", dir.display()));
+        return 1;
+    }
+
+    let mut entries = scan(&dir, &timelines_dir);
+    entries.retain(|e| session.is_none_or(|s| &e.session == s));
+    if m.get_flag("referenced") {
+        entries.retain(|e| !e.referenced_by.is_empty());
+    }
+    if m.get_flag("unreferenced") {
+        entries.retain(|e| e.referenced_by.is_empty());
+    }
+
+    match sort.as_str() {
+        "name" => entries.sort_by(|a, b| a.session.cmp(&b.session).then(a.path.cmp(&b.path))),
+        "size" => entries.sort_by_key(|e| e.size),
+        "duration" => entries.sort_by(|a, b| a.duration.total_cmp(&b.duration)),
+        _ => entries.sort_by(|a, b| a.recorded_at.cmp(&b.recorded_at).then(a.path.cmp(&b.path))),
+    }
+    if m.get_flag("reverse") {
+        entries.reverse();
+    }
+
+    if m.get_flag("json") {
+        match serde_json::to_string_pretty(&entries) {
+            Ok(s) => println!("{s}"),
+            Err(e) => {
+                eprintln_err(&format!("Failed to serialize cast list: {e}"));
+                return 1;
+            }
+        }
+        return 0;
+    }
+
+    println!(
+        "{:<20} {:<19} {:>9} {:>9} {:>4}  FILE",
+        "SESSION", "RECORDED", "SIZE", "DURATION", "REFS"
+    );
+    for e in &entries {
+        println!(
+            "{:<20} {:<19} {:>9} {:>9} {:>4}  {}",
+            e.session,
+            e.recorded_at
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "-".into()),
+            human_size(e.size),
+            human_duration(e.duration),
+            e.referenced_by.len(),
+            e.path.file_name().unwrap_or_default().to_string_lossy()
+        );
+    }
+    0
+}
+
+/// Parse an age like `30m`, `12h`, `7d` or `2w` (bare numbers are days).
+pub fn parse_age(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: i64 = num.parse().map_err(|_| format!("invalid age: {s:?}"))?;
+    let age = match unit {
+        "s" => Duration::try_seconds(n),
+        "m" => Duration::try_minutes(n),
+        "h" => Duration::try_hours(n),
+        "" | "d" => Duration::try_days(n),
+        "w" => Duration::try_weeks(n),
+        _ => return Err(format!("invalid age unit in {s:?} (use s, m, h, d or w)")),
+    };
+    age.ok_or_else(|| format!("age out of range: {s:?}"))
+}
+
+/// Move `path` into `trash`, as `NAME.N.cast` if `NAME.cast` is already
+/// there. Copies (then removes) across filesystems.
+fn trash_file(path: &Path, trash: &Path) -> io::Result<PathBuf> {
+    let name = Path::new(path.file_name().unwrap_or_default());
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = name
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut dest = trash.join(name);
+    let mut n = 1;
+    while dest.exists() {
+        dest = trash.join(format!("{stem}.{n}{ext}"));
+        n += 1;
+    }
+    match fs::rename(path, &dest) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            fs::copy(path, &dest)?;
+            fs::remove_file(path)?;
+        }
+        result => result?,
+    }
+    Ok(dest)
+}
+
+fn run_prune(m: &ArgMatches) -> i32 {
+    let Project {
+        casts: dir,
+        timelines: timelines_dir,
+        ..
+    } = build::project(m);
+    let trash = m
+        .get_one::<PathBuf>("trash")
+        .cloned()
+        .unwrap_or_else(|| dir.join(".trash"));
+    let yes = m.get_flag("yes");
+    let older_than = match parse_age(m.get_one::<String>("older_than").unwrap()) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln_err(&e);
+            return 1;
+        }
+    };
+
+    if !dir.is_dir() {
+        eprintln_err(&format!("Cast directory does not exist: {}", dir.display()));
+        return 1;
+    }
+    if !timelines_dir.is_dir() {
+        // Without timelines every cast looks unreferenced; refuse rather than trash them all.
+        eprintln_err(&format!(
+            "Timelines directory does not exist: {}",
+            timelines_dir.display()
+        ));
+        return 1;
+    }
+
+    let Some(cutoff) = Local::now().naive_local().checked_sub_signed(older_than) else {
+        eprintln_err(&format!(
+            "Age out of range: {}",
+            m.get_one::<String>("older_than").unwrap()
+        ));
+        return 1;
+    };
+    let mut victims: Vec<CastEntry> = scan(&dir, &timelines_dir)
+        .into_iter()
+        .filter(|e| e.referenced_by.is_empty())
+        .filter(|e| recorded_or_modified(e).is_some_and(|t| t < cutoff))
+        .collect();
+    victims.sort_by(|a, b| a.recorded_at.cmp(&b.recorded_at).then(a.path.cmp(&b.path)));
+
+    if victims.is_empty() {
+        eprintln!(
+            "No unreferenced casts older than {}.",
+            m.get_one::<String>("older_than").unwrap()
+        );
+        return 0;
+    }
+
+    let total: u64 = victims.iter().map(|e| e.size).sum();
+    if yes {
+        fsx::ensure_writable_dir(&trash);
+    }
+    let mut rc = 0;
+    for e in &victims {
+        if yes {
+            match trash_file(&e.path, &trash) {
+                Ok(_) => eprintln!("trashed {}", e.path.display()),
+                Err(err) => {
+                    eprintln_err(&format!("Failed to move {}: {err}", e.path.display()));
+                    rc = 1;
+                }
+            }
+        } else {
+            println!("{}\t{}", human_size(e.size), e.path.display());
+        }
+    }
+    if yes {
+        eprintln!(
+            "Moved {} cast(s) ({}) to {}",
+            victims.len(),
+            human_size(total),
+            trash.display()
+        );
+    } else {
+        eprintln!(
+            "{} unreferenced cast(s) ({}). Re-run with --yes to move them to {}",
+            victims.len(),
+            human_size(total),
+            trash.display()
+        );
+    }
+    rc
+}
+
+fn cast_files(m: &ArgMatches) -> Vec<PathBuf> {
+    if let Some(files) = m.get_many::<PathBuf>("files") {
+        return files.cloned().collect();
+    }
+    let dir = m
+        .get_one::<PathBuf>("dir")
+        .cloned()
+        .unwrap_or_else(fsx::default_cast_dir);
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "cast"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn run_check(m: &ArgMatches) -> i32 {
+    let files = cast_files(m);
+    if files.is_empty() {
+        eprintln_err("No casts to check.");
+        return 1;
+    }
+    let mut rc = 0;
+    for path in &files {
+        let bytes = match fs::read(path) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln_err(&format!("Failed to read {}: {e}", path.display()));
+                rc = 1;
+                continue;
+            }
+        };
+        let issues = cast::check(&bytes);
+        if issues.is_empty() {
+            println!("ok      {}", path.display());
+        } else {
+            rc = 1;
+            println!("BROKEN  {}", path.display());
+            for issue in issues {
+                println!("          {issue}");
+            }
+        }
+    }
+    if rc != 0 {
+        eprintln!("Run `casts repair FILE` to fix broken casts.");
+    }
+    rc
+}
+
+fn run_repair(m: &ArgMatches) -> i32 {
+    let files: Vec<PathBuf> = m.get_many::<PathBuf>("files").unwrap().cloned().collect();
+    let out = m.get_one::<PathBuf>("out");
+    let cols = *m.get_one::<u32>("cols").unwrap();
+    let rows = *m.get_one::<u32>("rows").unwrap();
+    if out.is_some() && files.len() > 1 {
+        eprintln_err("--out can only be used when repairing a single file.");
+        return 1;
+    }
+
+    let mut rc = 0;
+    for path in &files {
+        if let Err(e) = repair_file(path, out.map(PathBuf::as_path), cols, rows) {
+            eprintln_err(&format!("Failed to repair {}: {e}", path.display()));
+            rc = 1;
+        }
+    }
+    rc
+}
+
+fn repair_file(path: &Path, out: Option<&Path>, cols: u32, rows: u32) -> std::io::Result<()> {
+    let bytes = fs::read(path)?;
+    let repaired = cast::repair(&bytes, cols, rows);
+    if repaired.issues.is_empty() && out.is_none() {
+        println!("ok      {}", path.display());
+        return Ok(());
+    }
+    for issue in &repaired.issues {
+        eprintln!("{}: {issue}", path.display());
+    }
+    match out {
+        Some(out) => fs::write(out, &repaired.text)?,
+        None => {
+            // keep the original next to the fixed file, and earlier originals too
+            let backup = backup_path(path);
+            let mut tmp = path.as_os_str().to_owned();
+            tmp.push(".tmp");
+            fs::write(&tmp, &repaired.text)?;
+            fs::rename(path, &backup)?;
+            fs::rename(&tmp, path)?;
+            eprintln!("original kept as {}", backup.display());
+        }
+    }
+    println!("fixed   {}", out.unwrap_or(path).display());
+    Ok(())
+}
+
+/// `NAME.orig` beside `path`, or `NAME.orig.N` for the first free `N`.
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".orig");
+    let mut backup = PathBuf::from(&name);
+    let mut n = 1;
+    while backup.exists() {
+        backup = PathBuf::from(format!("{}.{n}", name.to_string_lossy()));
+        n += 1;
+    }
+    backup
+}
+
+fn recorded_or_modified(e: &CastEntry) -> Option<NaiveDateTime> {
+    e.recorded_at.or_else(|| {
+        let mtime = fs::metadata(&e.path).and_then(|md| md.modified()).ok()?;
+        Some(DateTime::<Local>::from(mtime).naive_local())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stem_with_timestamp() {
+        let (session, t) = parse_cast_stem("my-demo-20250102-030405");
+        assert_eq!(session, "my-demo");
+        assert_eq!(
+            t.unwrap().format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2025-01-02 03:04:05"
+        );
+    }
+
+    #[test]
+    fn ages() {
+        assert_eq!(parse_age("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_age("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_age("7").unwrap(), Duration::days(7));
+        assert_eq!(parse_age("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_age("soon").is_err());
+        assert!(parse_age("3y").is_err());
+        assert!(parse_age("99999999999999w").is_err());
+    }
+
+    #[test]
+    fn trash_keeps_earlier_casts_of_the_same_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let trash = root.join(".trash");
+        fs::create_dir_all(&trash).unwrap();
+        for body in ["first", "second"] {
+            fs::write(root.join("demo.cast"), body).unwrap();
+            trash_file(&root.join("demo.cast"), &trash).unwrap();
+        }
+        assert!(!root.join("demo.cast").exists());
+        assert_eq!(
+            fs::read_to_string(trash.join("demo.cast")).unwrap(),
+            "first"
+        );
+        assert_eq!(
+            fs::read_to_string(trash.join("demo.1.cast")).unwrap(),
+            "second"
+        );
+    }
+
+    #[test]
+    fn repair_keeps_earlier_originals() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let path = root.join("demo.cast");
+        for body in ["[1.0, \"o\", \"a\"]\n", "[2.0, \"o\", \"b\"]\n"] {
+            fs::write(&path, body).unwrap();
+            repair_file(&path, None, 80, 24).unwrap();
+        }
+        assert!(
+            fs::read_to_string(root.join("demo.cast.orig"))
+                .unwrap()
+                .contains("\"a\"")
+        );
+        assert!(
+            fs::read_to_string(root.join("demo.cast.orig.1"))
+                .unwrap()
+                .contains("\"b\"")
+        );
+    }
+
+    #[test]
+    fn stem_of_take() {
+        let (session, t) = parse_cast_stem("demo-20250102-030405.take3");
+        assert_eq!(session, "demo");
+        assert!(t.is_some());
+        assert_eq!(
+            fsx::take_path(Path::new("c/demo.take3.cast"), 4),
+            Path::new("c/demo.take4.cast")
+        );
+    }
+
+    #[test]
+    fn stem_without_timestamp() {
+        let (session, t) = parse_cast_stem("intro");
+        assert_eq!(session, "intro");
+        assert!(t.is_none());
+    }
+}
@@ -1 +1,9 @@
+pub mod build;
+pub mod casts;
+pub mod publish;
 pub mod record;
+pub mod serve;
+pub mod sessions;
+pub mod speech;
+#[cfg(feature = "web-terminal")]
+pub mod web_terminal;
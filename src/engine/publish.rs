@@ -0,0 +1,347 @@
+use crate::util::player::PlayerFile;
+use crate::util::{eprintln_err, fsx, http, inline, player, timeline};
+use clap::ArgMatches;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An asset referenced from a timeline item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Asset {
+    Cast,
+    Audio,
+}
+
+/// Where referenced files are looked up.
+struct Sources {
+    /// The player's `public/` directory (compiled timelines, speech).
+    public: PathBuf,
+    casts: PathBuf,
+}
+
+impl Sources {
+    /// The file behind `path` as the player would fetch it (see `serve`):
+    /// below `public/`, `/casts/` from the cast directory, and casts also by
+    /// file name in the cast directory.
+    fn find(&self, kind: Asset, path: &str) -> Option<PathBuf> {
+        let rel = path.trim_start_matches('/');
+        let mut candidates = vec![http::resolve(&self.public, rel)];
+        if kind == Asset::Cast {
+            if let Some(rest) = rel.strip_prefix("casts/") {
+                candidates.push(http::resolve(&self.casts, rest));
+            }
+            let name = Path::new(rel).file_name()?;
+            candidates.push(Some(self.casts.join(name)));
+        }
+        candidates.into_iter().flatten().find(|p| p.is_file())
+    }
+}
+
+/// `publish`: write a static site with the player and every compiled
+/// timeline, with the casts and audio they use copied next to them. With
+/// `--single-file`, all of it goes into one `.html` file instead.
+pub fn run_publish(m: &ArgMatches) -> i32 {
+    let out = m.get_one::<PathBuf>("output").unwrap();
+    let single_file = m.get_flag("single_file");
+    let player_dir = m.get_one::<PathBuf>("player").unwrap();
+    let dist = m.get_one::<PathBuf>("dist");
+    let base = m.get_one::<String>("base");
+    let sources = Sources {
+        public: player_dir.join("public"),
+        casts: m
+            .get_one::<PathBuf>("dir")
+            .cloned()
+            .unwrap_or_else(fsx::default_cast_dir),
+    };
+
+    let files = match player::files(dist.map(PathBuf::as_path)) {
+        Ok(f) if f.is_empty() => {
+            eprintln_err(
+                "This shell-scene was built without the player; build it (`npm run build` in player) and pass --dist player/dist",
+            );
+            return 1;
+        }
+        Ok(f) => f,
+        Err(e) => {
+            eprintln_err(&format!("Failed to read the player: {e}"));
+            return 1;
+        }
+    };
+    let compiled = sources.public.join("timelines");
+    let mut names = match timeline::compiled_names(&compiled) {
+        Ok(n) if !n.is_empty() => n,
+        Ok(_) | Err(_) => {
+            eprintln_err(&format!(
+                "No compiled timelines in {} (run `shell-scene build --player {}`)",
+                compiled.display(),
+                player_dir.display()
+            ));
+            return 1;
+        }
+    };
+    if let Some(wanted) = m.get_many::<String>("names") {
+        let wanted: Vec<String> = wanted.cloned().collect();
+        if let Some(missing) = wanted.iter().find(|n| !names.contains(n)) {
+            eprintln_err(&format!(
+                "No compiled timeline named {missing:?} in {}",
+                compiled.display()
+            ));
+            return 1;
+        }
+        names = wanted;
+    }
+    if !m.get_flag("force") {
+        if single_file && out.exists() {
+            eprintln_err(&format!(
+                "{} already exists (use --force to replace it)",
+                out.display()
+            ));
+            return 1;
+        }
+        if !single_file && fs::read_dir(out).is_ok_and(|mut d| d.next().is_some()) {
+            eprintln_err(&format!(
+                "{} is not empty (use --force to write into it)",
+                out.display()
+            ));
+            return 1;
+        }
+    }
+
+    // site path -> source file
+    let mut copies: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let mut problems = Vec::new();
+    let mut timelines = Vec::new();
+    for name in &names {
+        let path = compiled.join(format!("{name}.json"));
+        let mut items = match read_items(&path) {
+            Ok(items) => items,
+            Err(e) => {
+                problems.push(format!("{}: {e}", path.display()));
+                continue;
+            }
+        };
+        rewrite_assets(&mut items, |kind, src| {
+            let Some(file) = sources.find(kind, src) else {
+                problems.push(format!("{name}: {src} not found"));
+                return None;
+            };
+            let site_path = match kind {
+                Asset::Cast => format!("casts/{}", file.file_name()?.to_string_lossy()),
+                Asset::Audio => src.trim_start_matches('/').to_string(),
+            };
+            match copies.get(&site_path) {
+                Some(other) if *other != file => {
+                    problems.push(format!(
+                        "{name}: {} and {} would both be published as {site_path}",
+                        other.display(),
+                        file.display()
+                    ));
+                    None
+                }
+                _ => {
+                    copies.insert(site_path.clone(), file);
+                    Some(site_path)
+                }
+            }
+        });
+        timelines.push((name, items));
+    }
+    if !problems.is_empty() {
+        for p in &problems {
+            eprintln_err(p);
+        }
+        return 1;
+    }
+
+    let written = if single_file {
+        write_single_file(out, &files, &copies, &timelines)
+    } else {
+        write_site(out, base.map(String::as_str), &files, &copies, &timelines)
+    };
+    if let Err(e) = written {
+        eprintln_err(&format!("Failed to write {}: {e}", out.display()));
+        return 1;
+    }
+
+    let casts = copies.keys().filter(|p| p.starts_with("casts/")).count();
+    eprintln!(
+        "[publish] Wrote {} timeline(s), {casts} cast(s) and {} audio file(s) to {}",
+        timelines.len(),
+        copies.len() - casts,
+        out.display()
+    );
+    0
+}
+
+type Timelines<'a> = [(&'a String, Value)];
+
+fn write_site(
+    out: &Path,
+    base: Option<&str>,
+    files: &[PlayerFile],
+    copies: &BTreeMap<String, PathBuf>,
+    timelines: &Timelines,
+) -> io::Result<()> {
+    for (rel, bytes) in files {
+        match (rel.as_str(), base) {
+            ("index.html", Some(base)) => {
+                let html = player::with_base(&String::from_utf8_lossy(bytes), base);
+                write(out, rel, html.as_bytes())?;
+            }
+            _ => write(out, rel, bytes)?,
+        }
+    }
+    for (rel, src) in copies {
+        write(out, rel, &fs::read(src)?)?;
+    }
+    let mut names = Vec::new();
+    for (name, items) in timelines {
+        let json = json!({ "name": name, "items": items });
+        let json = serde_json::to_string_pretty(&json)?;
+        write(out, &format!("timelines/{name}.json"), json.as_bytes())?;
+        let module = timeline::speech_module(name, items);
+        write(
+            out,
+            &format!("timelines/{name}.speech.js"),
+            module.as_bytes(),
+        )?;
+        names.push(name.to_string());
+    }
+    let registry = serde_json::to_string_pretty(&timeline::registry(&names))?;
+    write(out, "timelines/registry.json", registry.as_bytes())
+}
+
+/// The player's `index.html` with its scripts and styles inlined, and the
+/// timelines registered by an inline script, with casts and audio as data
+/// URLs.
+fn write_single_file(
+    out: &Path,
+    files: &[PlayerFile],
+    copies: &BTreeMap<String, PathBuf>,
+    timelines: &Timelines,
+) -> io::Result<()> {
+    let find = |path: &str| {
+        let rel = path.trim_start_matches("./").trim_start_matches('/');
+        files.iter().find(|(p, _)| p == rel)
+    };
+    let Some((_, index)) = find("index.html") else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "the player has no index.html",
+        ));
+    };
+    let html = inline::inline_html(&String::from_utf8_lossy(index), |path| {
+        let (rel, bytes) = find(path)?;
+        Some((bytes.to_vec(), http::mime_type(Path::new(rel))))
+    });
+
+    let mut data = BTreeMap::new();
+    for (rel, src) in copies {
+        let mime = http::mime_type(Path::new(rel));
+        data.insert(rel.as_str(), inline::data_url(mime, &fs::read(src)?));
+    }
+    let mut script = String::from("<script>\n");
+    for (name, items) in timelines {
+        let mut items = items.clone();
+        rewrite_assets(&mut items, |_, src| data.get(src).cloned());
+        let (name, items) = (json!(name), items.to_string().replace("</", "<\\/"));
+        writeln!(
+            script,
+            "window.shellScene.registerTimeline({name}, {items}, {name});"
+        )
+        .unwrap();
+    }
+    script.push_str("</script>\n");
+    let html = match html.find("</head>") {
+        Some(i) => format!("{}{script}{}", &html[..i], &html[i..]),
+        None => script + &html,
+    };
+
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(out, html)
+}
+
+fn read_items(path: &Path) -> io::Result<Value> {
+    let json: Value = serde_json::from_slice(&fs::read(path)?)?;
+    match json.get("items") {
+        Some(items @ Value::Array(_)) => Ok(items.clone()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "expected { name, items[] }",
+        )),
+    }
+}
+
+/// Replace each cast path and audio `src` in the timeline `items` with what
+/// `publish` returns for it (a path relative to the site root, which is what
+/// the player's `withBase` expects). `None` leaves the reference as it was.
+fn rewrite_assets(items: &mut Value, mut publish: impl FnMut(Asset, &str) -> Option<String>) {
+    let mut rewrite = |v: &mut Value, kind| {
+        if let Some(new) = v.as_str().and_then(|src| publish(kind, src)) {
+            *v = Value::String(new);
+        }
+    };
+    for item in items.as_array_mut().into_iter().flatten() {
+        if item["type"] == "cast"
+            && let Some(path) = item.get_mut("castPath")
+        {
+            rewrite(path, Asset::Cast);
+        }
+        match item.get_mut("audio") {
+            Some(Value::Array(audio)) => {
+                for a in audio {
+                    if let Some(src) = a.get_mut("src") {
+                        rewrite(src, Asset::Audio);
+                    }
+                }
+            }
+            Some(a @ Value::Object(_)) => {
+                if let Some(src) = a.get_mut("src") {
+                    rewrite(src, Asset::Audio);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn write(root: &Path, rel: &str, bytes: &[u8]) -> io::Result<()> {
+    let path = root.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rewrites_casts_and_audio() {
+        let mut items = json!([
+            { "type": "card", "title": "Hi", "audio": { "src": "/speech/t/hi.opus" } },
+            { "type": "transition", "name": "fade", "durationFrames": 10 },
+            {
+                "type": "cast",
+                "castPath": "/home/me/casts/demo.cast",
+                "audio": [{ "src": "speech/t/a.opus" }, { "src": "missing.opus" }]
+            }
+        ]);
+        rewrite_assets(&mut items, |kind, src| match kind {
+            Asset::Cast => Some("casts/demo.cast".into()),
+            Asset::Audio if src.starts_with("missing") => None,
+            Asset::Audio => Some(src.trim_start_matches('/').into()),
+        });
+        assert_eq!(items[0]["audio"]["src"], "speech/t/hi.opus");
+        assert_eq!(items[2]["castPath"], "casts/demo.cast");
+        assert_eq!(items[2]["audio"][0]["src"], "speech/t/a.opus");
+        assert_eq!(items[2]["audio"][1]["src"], "missing.opus");
+    }
+}
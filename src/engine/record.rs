@@ -1,44 +1,208 @@
-use crate::util::{deps, fsx, net, proc};
+use crate::engine::casts;
+use crate::util::browser::{self, Open};
+use crate::util::config::{self, ClientOptions, CursorStyle, Renderer};
+use crate::util::host::{Container, Host};
+use crate::util::pause::{self, Mark};
+use crate::util::recorder::Recorder;
+use crate::util::{deps, eprintln_err, eprintln_warn, fsx, net, proc};
 use clap::ArgMatches;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 pub fn run_record(m: &ArgMatches) -> i32 {
+    match m.subcommand() {
+        Some(("pause", m)) => return run_pause_resume(m, Mark::Pause),
+        Some(("resume", m)) => return run_pause_resume(m, Mark::Resume),
+        _ => {}
+    }
+
     // Parse
     let session = m.get_one::<String>("session").unwrap().to_owned();
     let cols = *m.get_one::<u32>("cols").unwrap();
     let rows = *m.get_one::<u32>("rows").unwrap();
     let port_start = *m.get_one::<u16>("port").unwrap();
-    let font_size = *m.get_one::<u32>("font_size").unwrap();
+    let theme = match m.get_one::<String>("theme").map(|t| config::parse_theme(t)) {
+        Some(Ok(t)) => Some(t),
+        Some(Err(e)) => {
+            eprintln_err(&format!("Failed to read theme: {e}"));
+            return 2;
+        }
+        None => None,
+    };
+    let cli_client = ClientOptions {
+        font_size: m.get_one::<u32>("font_size").copied(),
+        font_family: m.get_one::<String>("font_family").cloned(),
+        line_height: m.get_one::<f64>("line_height").copied(),
+        cursor_style: m.get_one::<CursorStyle>("cursor_style").copied(),
+        cursor_blink: m.get_one::<bool>("cursor_blink").copied(),
+        renderer: m.get_one::<Renderer>("renderer").copied(),
+        theme,
+    };
+    let bind = *m.get_one::<IpAddr>("bind").unwrap();
+    let credential = m.get_one::<String>("credential").cloned();
+    let tls = m
+        .get_one::<PathBuf>("tls_cert")
+        .cloned()
+        .zip(m.get_one::<PathBuf>("tls_key").cloned());
     let mut out = m.get_one::<PathBuf>("out").cloned();
     let mut workdir = m.get_one::<PathBuf>("workdir").cloned();
     let kill = *m.get_one::<bool>("kill_on_detach").unwrap_or(&false);
+    let append = m.get_flag("append");
+    let recorder = *m.get_one::<Recorder>("recorder").unwrap();
+    let take = m.get_one::<u32>("take").copied();
+    let clear = m.get_flag("clear");
+    let host = host_from_matches(m);
+    let container = container_from_matches(m, &host);
+    let cfg = match config::load(m.get_one::<PathBuf>("config").map(PathBuf::as_path)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln_err(&format!("Failed to load config: {e}"));
+            return 2;
+        }
+    };
+    let defaults = ClientOptions {
+        font_size: Some(24),
+        ..Default::default()
+    };
+    let client_opts = cli_client.or(cfg.ttyd).or(defaults);
+    let client = match client_opts.ttyd_options() {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln_err(&format!("Invalid ttyd options: {e}"));
+            return 2;
+        }
+    };
+    let server = match m.get_one::<String>("server").map(String::as_str) {
+        Some(s) => s,
+        None if cfg!(feature = "web-terminal") && !deps::have("ttyd") => "builtin",
+        None => "ttyd",
+    };
+    if server == "builtin" {
+        if !cfg!(feature = "web-terminal") {
+            eprintln_err(
+                "This build has no built-in web terminal (cargo feature web-terminal); use ttyd.",
+            );
+            return 2;
+        }
+        if tls.is_some() {
+            eprintln_err("--tls-cert/--tls-key need --server ttyd.");
+            return 2;
+        }
+    }
+
     // deps
-    deps::require_cmds(&["ttyd", "tmux", "asciinema"]);
+    let mut required = Vec::new();
+    if recorder == Recorder::Asciinema {
+        required.push("asciinema");
+    }
+    if server == "ttyd" {
+        required.push("ttyd");
+    }
+    match &host {
+        Host::Local => required.push("tmux"),
+        Host::Ssh { ssh, .. } => required.push(ssh),
+    }
+    if let (Some(c), true) = (&container, host.is_local()) {
+        required.push(&c.runtime);
+    }
+    deps::require_cmds(&required);
     deps::warn_optionals();
 
     // defaults
     let home = fsx::home_dir();
-    if workdir.is_none() {
+    if workdir.is_none() && host.is_local() {
         workdir = Some(home.clone());
     }
+    if out.is_none() && (append || take.is_some()) {
+        // continue from the session's previous recording
+        out = casts::latest_session_cast(&fsx::default_cast_dir(), &session);
+        if out.is_none() {
+            eprintln_warn(&format!(
+                "No previous cast for session {session:?}; starting a new one."
+            ));
+        }
+    }
     if out.is_none() {
-        out = Some(home.join("casts").join(format!(
+        out = Some(fsx::default_cast_dir().join(format!(
             "{}-{}.cast",
             session,
             fsx::now_yyyymmdd_hhmmss()
         )));
     }
 
-    let out = out.unwrap();
-    let workdir = workdir.unwrap();
+    let mut out = out.unwrap();
+    if let Some(n) = take {
+        out = fsx::take_path(&out, n);
+        if out.exists() {
+            eprintln_err(&format!(
+                "Take {n} already exists: {} (choose another --take)",
+                out.display()
+            ));
+            return 1;
+        }
+    }
 
-    // validations
-    fsx::validate_workdir(&workdir);
+    // validations (a remote workdir is checked by tmux on the remote host)
+    if let (Some(workdir), true) = (&workdir, host.is_local()) {
+        fsx::validate_workdir(workdir);
+    }
     fsx::ensure_writable_dir(out.parent().unwrap_or_else(|| Path::new(".")));
 
-    let port = net::find_free_port(port_start);
+    if let Some(cred) = &credential
+        && !cred.contains(':')
+    {
+        eprintln_err("--credential must be USER:PASSWORD");
+        return 2;
+    }
+    if let Some((cert, key)) = &tls {
+        for f in [cert, key] {
+            if !f.is_file() {
+                eprintln_err(&format!("TLS file not found: {}", f.display()));
+                return 2;
+            }
+        }
+    }
+    if !bind.is_loopback() && credential.is_none() {
+        eprintln_warn(&format!(
+            "The recording UI will accept anyone who can reach {bind}; consider --credential USER:PASSWORD."
+        ));
+    }
+
+    let browser = m.get_one::<String>("browser").cloned();
+    let open = if m.get_flag("no_open") {
+        Open::No
+    } else if m.get_flag("app_window") {
+        let (width, height) = browser::window_size(
+            cols,
+            rows,
+            client_opts.font_size.unwrap_or(24),
+            client_opts.line_height.unwrap_or(1.0),
+        );
+        Open::AppWindow {
+            browser,
+            width,
+            height,
+        }
+    } else {
+        Open::Browser(browser)
+    };
 
     let exe = std::env::current_exe().expect("failed to get current exe path");
+    let program = match server {
+        "builtin" => vec![exe.to_string_lossy().to_string(), "web-terminal".into()],
+        _ => vec!["ttyd".to_string()],
+    };
+    let ttyd = proc::TtydOptions {
+        program,
+        port: net::find_free_port(bind, port_start),
+        client,
+        bind,
+        credential,
+        tls,
+        open,
+    };
+
     let mut cmd_and_args = vec![
         exe.to_string_lossy().to_string(),
         "record-hook".to_string(),
@@ -51,12 +215,38 @@ pub fn run_record(m: &ArgMatches) -> i32 {
         rows.to_string(),
         "--out".to_string(),
         out.to_string_lossy().to_string(),
-        "--workdir".to_string(),
-        workdir.to_string_lossy().to_string(),
+        "--recorder".to_string(),
+        recorder.as_str().to_string(),
     ];
+    if let Some(workdir) = &workdir {
+        cmd_and_args.push("--workdir".into());
+        cmd_and_args.push(workdir.to_string_lossy().to_string());
+    }
+    if let Host::Ssh { ssh, dest } = &host {
+        cmd_and_args.extend([
+            "--ssh".into(),
+            dest.clone(),
+            "--ssh-command".into(),
+            ssh.clone(),
+        ]);
+    }
+    if let Some(c) = &container {
+        cmd_and_args.extend([
+            "--container".into(),
+            c.image.clone(),
+            "--container-runtime".into(),
+            c.runtime.clone(),
+        ]);
+    }
     if kill {
         cmd_and_args.push("--kill-on-detach".into());
     }
+    if append {
+        cmd_and_args.push("--append".into());
+    }
+    if clear {
+        cmd_and_args.push("--clear".into());
+    }
 
     let envs = vec![
         ("SESSION", session.clone()),
@@ -73,7 +263,7 @@ pub fn run_record(m: &ArgMatches) -> i32 {
         ),
     ];
 
-    proc::spawn_ttyd_and_wait(port, font_size, &session, &envs, &cmd_and_args)
+    proc::spawn_ttyd_and_wait(&ttyd, &session, &host, &out, &envs, &cmd_and_args)
 }
 
 pub fn run_record_hook(m: &ArgMatches) -> i32 {
@@ -84,13 +274,18 @@ pub fn run_record_hook(m: &ArgMatches) -> i32 {
     let mut out = m.get_one::<PathBuf>("out").cloned();
     let mut workdir = m.get_one::<PathBuf>("workdir").cloned();
     let kill = m.get_flag("kill_on_detach");
+    let append = m.get_flag("append");
+    let recorder = *m.get_one::<Recorder>("recorder").unwrap();
+    let clear = m.get_flag("clear");
+    let host = host_from_matches(m);
+    let container = container_from_matches(m, &host);
 
     let home = fsx::home_dir();
-    if workdir.is_none() {
+    if workdir.is_none() && host.is_local() {
         workdir = Some(home.clone());
     }
     if out.is_none() {
-        out = Some(home.join("casts").join(format!(
+        out = Some(fsx::default_cast_dir().join(format!(
             "{}-{}.cast",
             session,
             fsx::now_yyyymmdd_hhmmss()
@@ -98,10 +293,171 @@ pub fn run_record_hook(m: &ArgMatches) -> i32 {
     }
 
     let out = out.unwrap();
-    let workdir = workdir.unwrap();
 
-    fsx::validate_workdir(&workdir);
+    if let (Some(workdir), true) = (&workdir, host.is_local()) {
+        fsx::validate_workdir(workdir);
+    }
     fsx::ensure_writable_dir(out.parent().unwrap_or_else(|| Path::new(".")));
 
-    proc::record_flow(&session, cols, rows, &out, &workdir, kill)
+    proc::record_flow(&proc::RecordSpec {
+        session,
+        cols,
+        rows,
+        ascii_out: out,
+        working_dir: workdir,
+        host,
+        container,
+        kill_on_detach: kill,
+        append,
+        clear,
+        recorder,
+    })
+}
+
+/// `record pause|resume`: mark the pause log of a running recording. The
+/// paused stretch is cut from the cast when the recording ends.
+fn run_pause_resume(m: &ArgMatches, mark: Mark) -> i32 {
+    let session = m.get_one::<String>("session").unwrap();
+    let sock = format!("ttyd-{session}");
+    let log = match proc::tmux_output(&["-L", &sock, "show-options", "-gqv", pause::TMUX_OPTION]) {
+        Ok(s) if !s.is_empty() => PathBuf::from(s),
+        _ => {
+            eprintln_err(&format!("No recording in progress for session {session:?}"));
+            return 1;
+        }
+    };
+
+    let paused = pause::is_paused(&log);
+    if (mark == Mark::Pause) == paused {
+        eprintln_warn(&format!(
+            "Recording is already {}.",
+            if paused { "paused" } else { "running" }
+        ));
+        return 0;
+    }
+    if let Err(e) = pause::append(&log, mark) {
+        eprintln_err(&format!("Failed to write {}: {e}", log.display()));
+        return 1;
+    }
+
+    match mark {
+        Mark::Pause => {
+            // shown while paused, so it never ends up in the cast
+            let _ = proc::run_tmux(&[
+                "-L",
+                &sock,
+                "display-message",
+                "Recording paused (prefix R to resume)",
+            ]);
+            eprintln!("Recording paused.");
+        }
+        _ => {
+            // redraw so playback picks up whatever changed while paused
+            let clients = proc::tmux_output(&["-L", &sock, "list-clients", "-F", "#{client_name}"])
+                .unwrap_or_default();
+            for client in clients.lines() {
+                let _ = proc::run_tmux(&["-L", &sock, "refresh-client", "-t", client]);
+            }
+            eprintln!("Recording resumed.");
+        }
+    }
+    0
+}
+
+/// `--ssh DEST` (with `--ssh-command`) selects a remote tmux host.
+fn host_from_matches(m: &ArgMatches) -> Host {
+    match m.get_one::<String>("ssh") {
+        Some(dest) => Host::Ssh {
+            ssh: m.get_one::<String>("ssh_command").unwrap().clone(),
+            dest: dest.clone(),
+        },
+        None => Host::Local,
+    }
+}
+
+/// `--container IMAGE` with `--container-runtime` (default: podman, else
+/// docker, whichever `host` has).
+fn container_from_matches(m: &ArgMatches, host: &Host) -> Option<Container> {
+    let image = m.get_one::<String>("container")?;
+    let runtime = m
+        .get_one::<String>("container_runtime")
+        .cloned()
+        .unwrap_or_else(|| {
+            if host.has("podman") {
+                "podman"
+            } else {
+                "docker"
+            }
+            .to_string()
+        });
+    Some(Container {
+        runtime,
+        image: image.clone(),
+    })
+}
+
+/// `stage`: create the recording session ahead of time so setup can happen
+/// off-camera. `record --session NAME --clear` then picks it up.
+pub fn run_stage(m: &ArgMatches) -> i32 {
+    let session = m.get_one::<String>("session").unwrap().to_owned();
+    let cols = *m.get_one::<u32>("cols").unwrap();
+    let rows = *m.get_one::<u32>("rows").unwrap();
+    let workdir = m
+        .get_one::<PathBuf>("workdir")
+        .cloned()
+        .unwrap_or_else(fsx::home_dir);
+    let runs: Vec<&String> = m.get_many::<String>("run").unwrap_or_default().collect();
+    let detach = m.get_flag("detach");
+
+    deps::require_cmds(&["tmux"]);
+    fsx::validate_workdir(&workdir);
+
+    let sock = format!("ttyd-{session}");
+    if let Err(e) = proc::prepare_session(
+        &Host::Local,
+        &sock,
+        &session,
+        cols,
+        rows,
+        Some(&workdir),
+        None,
+    ) {
+        eprintln_err(&format!("Failed to create tmux session: {e}"));
+        return 2;
+    }
+    for cmd in runs {
+        // the text literally (not as key names), then Enter; tmux takes a
+        // trailing `;` for a command separator unless it is escaped
+        let text = match cmd.strip_suffix(';') {
+            Some(rest) => format!("{rest}\\;"),
+            None => cmd.to_string(),
+        };
+        let typed = proc::run_tmux(&["-L", &sock, "send-keys", "-l", "-t", &session, "--", &text])
+            .and_then(|()| proc::run_tmux(&["-L", &sock, "send-keys", "-t", &session, "Enter"]));
+        if let Err(e) = typed {
+            eprintln_err(&format!("Failed to run {cmd:?}: {e}"));
+            return 1;
+        }
+    }
+
+    eprintln!("[stage] Session {session:?} is ready ({cols}x{rows}).");
+    let rc = if detach {
+        eprintln!("[stage] Attach with: shell-scene sessions attach --session {session}");
+        0
+    } else {
+        eprintln!("[stage] Set things up, then detach with prefix d (Ctrl-b d).");
+        match Command::new("tmux")
+            .args(["-L", &sock, "attach", "-t", &session])
+            .env_remove("TMUX")
+            .status()
+        {
+            Ok(s) => s.code().unwrap_or(1),
+            Err(e) => {
+                eprintln_err(&format!("Failed to run tmux: {e}"));
+                1
+            }
+        }
+    };
+    eprintln!("[stage] Start recording with: shell-scene record --session {session} --clear");
+    rc
 }
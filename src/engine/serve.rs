@@ -0,0 +1,387 @@
+use crate::engine::{build, speech};
+use crate::util::browser::{self, Open};
+use crate::util::compile::{self, Project};
+use crate::util::speech::Options;
+use crate::util::watch::Snapshot;
+use crate::util::{eprintln_err, eprintln_warn, http, net, timeline};
+use clap::ArgMatches;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, StatusCode};
+
+/// Event stream that tells open players to reload.
+const EVENTS_PATH: &str = "/__shell-scene/events";
+const RELOAD_SCRIPT: &str = "<script>new EventSource(\"/__shell-scene/events\").onmessage = () => location.reload();</script>";
+
+/// Directories served under URL prefixes, looked up in order.
+struct Site {
+    mounts: Vec<(&'static str, PathBuf)>,
+    /// Served for unknown extension-less paths (client-side routes).
+    index: PathBuf,
+    reload: Option<Arc<Reloader>>,
+}
+
+/// Players connected to the event stream.
+#[derive(Default)]
+struct Reloader {
+    clients: Mutex<Vec<Sender<()>>>,
+}
+
+impl Reloader {
+    fn subscribe(&self) -> Receiver<()> {
+        let (tx, rx) = mpsc::channel();
+        self.clients.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Tell every connected player to reload; returns how many there were.
+    fn notify(&self) -> usize {
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|tx| tx.send(()).is_ok());
+        clients.len()
+    }
+}
+
+/// How changed timelines are recompiled.
+enum Rebuild {
+    /// `build`, in process, with its speech step unless `--no-speech`.
+    Builtin(Option<Options>),
+    /// A shell command, run in the player project.
+    Command(String),
+    Skip,
+}
+
+/// `serve`: preview presentations from the built player, with the project's
+/// timelines, speech and casts served next to it. Open players reload when
+/// those change; edited `.timeline` files are recompiled first.
+pub fn run_serve(m: &ArgMatches) -> i32 {
+    let project = build::project(m);
+    let player = &project.player;
+    let dist = m
+        .get_one::<PathBuf>("dist")
+        .cloned()
+        .unwrap_or_else(|| player.join("dist"));
+    let bind = *m.get_one::<IpAddr>("bind").unwrap();
+    let port_start = *m.get_one::<u16>("port").unwrap();
+    let rebuild = match m.get_one::<String>("rebuild") {
+        None => Rebuild::Builtin(speech::options(m)),
+        Some(cmd) if cmd.trim().is_empty() => Rebuild::Skip,
+        Some(cmd) => Rebuild::Command(cmd.clone()),
+    };
+    let reload = (!m.get_flag("no_reload")).then(|| Arc::new(Reloader::default()));
+
+    let index = dist.join("index.html");
+    if !index.is_file() {
+        eprintln_err(&format!(
+            "No built player in {} (run `npm run build` in {})",
+            dist.display(),
+            player.display()
+        ));
+        return 1;
+    }
+    if let Some(reloader) = &reload {
+        let (project, reloader) = (project.clone(), reloader.clone());
+        thread::spawn(move || watch_and_reload(&project, &rebuild, &reloader));
+    }
+    let site = Arc::new(Site {
+        mounts: vec![
+            ("/casts/", project.casts.clone()),
+            // public/ is newer than the copy vite put in dist/
+            ("/", player.join("public")),
+            ("/", dist.clone()),
+        ],
+        index,
+        reload,
+    });
+
+    let port = net::find_free_port(bind, port_start);
+    let server = match tiny_http::Server::http((bind, port)) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln_err(&format!("Failed to listen on {bind}:{port}: {e}"));
+            return 1;
+        }
+    };
+    let url = net::url("http", net::local_addr(bind), port);
+    eprintln!(
+        "[serve] Serving {} at {url}. Press Ctrl-C to stop.",
+        dist.display()
+    );
+    let open = if m.get_flag("no_open") {
+        Open::No
+    } else {
+        Open::Browser(m.get_one::<String>("browser").cloned())
+    };
+    browser::open(&open, &url);
+
+    for req in server.incoming_requests() {
+        let site = site.clone();
+        thread::spawn(move || {
+            if let Err(e) = serve_request(&site, req) {
+                log::debug!("serve: {e}");
+            }
+        });
+    }
+    0
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("valid header")
+}
+
+fn serve_request(site: &Site, req: Request) -> io::Result<()> {
+    if !matches!(req.method(), Method::Get | Method::Head) {
+        return req.respond(Response::empty(405));
+    }
+    let url = req.url().to_string();
+    if let Some(reloader) = &site.reload
+        && url == EVENTS_PATH
+    {
+        return serve_events(reloader, req);
+    }
+    let Some(path) = lookup(site, &url) else {
+        return req.respond(Response::from_string("Not found").with_status_code(404));
+    };
+    log::debug!("serve: {url} -> {}", path.display());
+    if site.reload.is_some() && path == site.index {
+        let html = std::fs::read_to_string(&path)?;
+        let html = match html.rfind("</body>") {
+            Some(i) => format!("{}{RELOAD_SCRIPT}{}", &html[..i], &html[i..]),
+            None => html + RELOAD_SCRIPT,
+        };
+        return req.respond(
+            Response::from_string(html)
+                .with_header(header("Content-Type", http::mime_type(&path)))
+                .with_header(header("Cache-Control", "no-cache")),
+        );
+    }
+
+    let mut file = File::open(&path)?;
+    let len = file.metadata()?.len();
+    let mut headers = vec![
+        header("Content-Type", http::mime_type(&path)),
+        header("Accept-Ranges", "bytes"),
+        header("Cache-Control", "no-cache"),
+    ];
+    let range = req
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Range"))
+        .map(|h| h.value.as_str().to_string());
+    let Some(range) = range else {
+        let body = Response::new(StatusCode(200), headers, file, Some(len as usize), None);
+        return req.respond(body);
+    };
+    match http::parse_range(&range, len) {
+        Some((start, end)) => {
+            file.seek(SeekFrom::Start(start))?;
+            let count = end - start + 1;
+            headers.push(header(
+                "Content-Range",
+                &format!("bytes {start}-{end}/{len}"),
+            ));
+            let body = file.take(count);
+            req.respond(Response::new(
+                StatusCode(206),
+                headers,
+                body,
+                Some(count as usize),
+                None,
+            ))
+        }
+        None => req.respond(
+            Response::empty(416).with_header(header("Content-Range", &format!("bytes */{len}"))),
+        ),
+    }
+}
+
+/// The file for `url`: the first mount that has it, a directory's
+/// `index.html`, or the player's index for client-side routes.
+fn lookup(site: &Site, url: &str) -> Option<PathBuf> {
+    for (prefix, root) in &site.mounts {
+        let Some(rest) = url.strip_prefix(prefix) else {
+            continue;
+        };
+        let path = http::resolve(root, rest)?;
+        if path.is_file() {
+            return Some(path);
+        }
+        let index = path.join("index.html");
+        if index.is_file() {
+            return Some(index);
+        }
+    }
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    Path::new(path)
+        .extension()
+        .is_none()
+        .then(|| site.index.clone())
+}
+
+/// Server-sent events: one `reload` message per change, with comments in
+/// between so closed connections are noticed.
+fn serve_events(reloader: &Reloader, req: Request) -> io::Result<()> {
+    let rx = reloader.subscribe();
+    let mut w = req.into_writer();
+    w.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n",
+    )?;
+    w.flush()?;
+    loop {
+        match rx.recv_timeout(Duration::from_secs(15)) {
+            Ok(()) => w.write_all(b"data: reload\n\n")?,
+            Err(RecvTimeoutError::Timeout) => w.write_all(b": ping\n\n")?,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        w.flush()?;
+    }
+}
+
+/// The `.timeline` files to recompile for the `changed` files: those edited
+/// and those using a changed cast. `None` (all of them) once a timeline is
+/// removed, as its name leaves the registry.
+fn affected_timelines(project: &Project, changed: &BTreeSet<PathBuf>) -> Option<BTreeSet<PathBuf>> {
+    let mut out = BTreeSet::new();
+    let mut casts = Vec::new();
+    for p in changed {
+        if p.extension().is_some_and(|e| e == "cast") {
+            casts.push(p);
+        } else if p.starts_with(&project.timelines) {
+            if !p.exists() {
+                return None;
+            }
+            out.insert(p.clone());
+        }
+    }
+    if !casts.is_empty() {
+        // a recorded cast changes what the timelines using it compile to;
+        // so does one added or removed ahead of the file a reference resolves to
+        out.extend(
+            timeline::cast_refs(&project.timelines)
+                .into_iter()
+                .filter(|r| {
+                    let mut looked_at =
+                        compile::cast_candidates(&r.timeline, &r.path, &project.casts);
+                    if let Some(i) = looked_at.iter().position(|p| p.is_file()) {
+                        looked_at.truncate(i + 1);
+                    }
+                    casts.iter().any(|c| looked_at.contains(c))
+                })
+                .map(|r| r.timeline),
+        );
+    }
+    Some(out)
+}
+
+/// Poll the timelines (and casts kept beside them), casts and speech for changes. Once a burst of changes
+/// settles (a cast being recorded keeps changing), recompile the timelines
+/// the changes affect and reload the players.
+fn watch_and_reload(project: &Project, rebuild: &Rebuild, reloader: &Reloader) {
+    let (compiled, speech) = (project.compiled(), project.public().join("speech"));
+    let snapshot = || {
+        Snapshot::take(&[
+            (&project.timelines, &["timeline", "cast"]),
+            (&project.casts, &["cast"]),
+            (&compiled, &[]),
+            (&speech, &[]),
+        ])
+    };
+    let mut last = snapshot();
+    let mut pending: BTreeSet<PathBuf> = BTreeSet::new();
+    loop {
+        thread::sleep(Duration::from_millis(500));
+        let next = snapshot();
+        let changed = last.changed(&next);
+        last = next;
+        if !changed.is_empty() {
+            pending.extend(changed);
+            continue;
+        }
+        if pending.is_empty() {
+            continue;
+        }
+
+        for p in &pending {
+            eprintln!("[serve] Changed: {}", p.display());
+        }
+        let affected = affected_timelines(project, &pending);
+        pending.clear();
+        if affected.as_ref().is_none_or(|a| !a.is_empty()) {
+            match rebuild {
+                Rebuild::Builtin(speech) => {
+                    if !build::build(project, speech.as_ref(), affected.as_ref()) {
+                        eprintln_warn("Rebuild failed; reloading anyway.");
+                    }
+                }
+                Rebuild::Command(cmd) => {
+                    eprintln!("[serve] Rebuilding timelines: {cmd}");
+                    match Command::new("sh")
+                        .arg("-c")
+                        .arg(cmd)
+                        .current_dir(&project.player)
+                        .status()
+                    {
+                        Ok(s) if s.success() => {}
+                        Ok(s) => eprintln_warn(&format!("Rebuild failed ({s}); reloading anyway.")),
+                        Err(e) => eprintln_warn(&format!("Failed to run the rebuild: {e}")),
+                    }
+                }
+                Rebuild::Skip => {}
+            }
+            // the rebuild's own output is part of this change
+            last = snapshot();
+        }
+        let n = reloader.notify();
+        if n > 0 {
+            eprintln!("[serve] Reloading {n} player(s)");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn casts_map_to_timelines_by_resolved_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let project = Project {
+            player: root.join("player"),
+            timelines: root.join("talk"),
+            casts: root.join("casts"),
+        };
+        fs::create_dir_all(&project.timelines).unwrap();
+        fs::create_dir_all(&project.casts).unwrap();
+        for p in [
+            project.timelines.join("demo.cast"),
+            project.casts.join("demo.cast"),
+        ] {
+            fs::write(p, "{\"version\":2}\n").unwrap();
+        }
+        let tl = project.timelines.join("t.timeline");
+        fs::write(&tl, "timeline: t\ncast demo.cast 80x24\n").unwrap();
+
+        let affected = |p: PathBuf| affected_timelines(&project, &BTreeSet::from([p])).unwrap();
+        // the cast beside the timeline shadows the one in the casts dir
+        assert_eq!(
+            affected(project.timelines.join("demo.cast")),
+            BTreeSet::from([tl.clone()])
+        );
+        assert!(affected(project.casts.join("demo.cast")).is_empty());
+        fs::remove_file(project.timelines.join("demo.cast")).unwrap();
+        assert_eq!(
+            affected(project.casts.join("demo.cast")),
+            BTreeSet::from([tl.clone()])
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crate::util::{eprintln_err, eprintln_warn, pause, proc};
+use chrono::{DateTime, Local};
+use clap::ArgMatches;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A tmux session living on one of the `ttyd-{session}` sockets.
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub name: String,
+    pub size: String,
+    pub workdir: String,
+    pub created: Option<i64>,
+    pub attached: usize,
+    /// Pause log of the recording in progress, if any.
+    pub recording: Option<PathBuf>,
+}
+
+pub fn run_sessions(m: &ArgMatches) -> i32 {
+    match m.subcommand() {
+        Some(("list", _)) => run_list(),
+        Some(("attach", m)) => run_attach(m),
+        Some(("kill", m)) => run_kill(m),
+        Some(("kill-all", m)) => run_kill_all(m),
+        _ => 1,
+    }
+}
+
+/// Sessions on `sock`. An empty list means the server is gone (stale
+/// socket); other tmux failures, like a server of another tmux version,
+/// are errors.
+pub fn sessions_on(sock: &str) -> io::Result<Vec<SessionInfo>> {
+    let format = "#{session_name}\t#{window_width}x#{window_height}\t#{pane_current_path}\t#{session_created}\t#{session_attached}";
+    let out = Command::new("tmux")
+        .args(["-L", sock, "list-sessions", "-F", format])
+        .output()?;
+    if !out.status.success() {
+        let err = String::from_utf8_lossy(&out.stderr);
+        if server_is_gone(&err) {
+            return Ok(Vec::new());
+        }
+        return Err(io::Error::other(format!("tmux: {}", err.trim())));
+    }
+    let out = String::from_utf8_lossy(&out.stdout);
+    let recording = proc::tmux_output(&["-L", sock, "show-options", "-gqv", pause::TMUX_OPTION])
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from);
+    Ok(out
+        .lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split('\t').collect();
+            if f.len() != 5 {
+                return None;
+            }
+            Some(SessionInfo {
+                name: f[0].to_string(),
+                size: f[1].to_string(),
+                workdir: f[2].to_string(),
+                created: f[3].parse().ok(),
+                attached: f[4].parse().unwrap_or(0),
+                recording: recording.clone(),
+            })
+        })
+        .collect())
+}
+
+/// Whether tmux's error means nothing is listening on the socket.
+fn server_is_gone(stderr: &str) -> bool {
+    [
+        "no server running",
+        "Connection refused",
+        "No such file or directory",
+    ]
+    .iter()
+    .any(|m| stderr.contains(m))
+}
+
+fn run_list() -> i32 {
+    let socks = proc::ttyd_sockets();
+    if socks.is_empty() {
+        eprintln!("No recording sessions.");
+        return 0;
+    }
+    println!(
+        "{:<16} {:<9} {:>8} {:<10} WORKDIR",
+        "SESSION", "SIZE", "AGE", "STATE"
+    );
+    for sock in socks {
+        let sessions = match sessions_on(&sock) {
+            Ok(s) => s,
+            Err(e) => {
+                println!(
+                    "{:<16} {:<9} {:>8} {:<10} -",
+                    sock.trim_start_matches("ttyd-"),
+                    "-",
+                    "-",
+                    "unknown"
+                );
+                eprintln_warn(&format!("{sock}: {e}"));
+                continue;
+            }
+        };
+        if sessions.is_empty() {
+            println!(
+                "{:<16} {:<9} {:>8} {:<10} -",
+                sock.trim_start_matches("ttyd-"),
+                "-",
+                "-",
+                "stale"
+            );
+            continue;
+        }
+        for s in sessions {
+            println!(
+                "{:<16} {:<9} {:>8} {:<10} {}",
+                s.name,
+                s.size,
+                s.created.map(age).unwrap_or_else(|| "-".into()),
+                state(&s),
+                s.workdir
+            );
+        }
+    }
+    0
+}
+
+fn state(s: &SessionInfo) -> &'static str {
+    match &s.recording {
+        Some(log) if pause::is_paused(log) => "paused",
+        Some(_) => "recording",
+        None if s.attached > 0 => "attached",
+        None => "idle",
+    }
+}
+
+fn age(created: i64) -> String {
+    let Some(created) = DateTime::from_timestamp(created, 0) else {
+        return "-".into();
+    };
+    let secs = (Local::now().timestamp() - created.timestamp()).max(0);
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+fn run_attach(m: &ArgMatches) -> i32 {
+    let session = m.get_one::<String>("session").unwrap();
+    let sock = format!("ttyd-{session}");
+    match sessions_on(&sock) {
+        Ok(s) if s.is_empty() => {
+            eprintln_err(&format!("No recording session named {session:?}"));
+            return 1;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln_err(&format!("{sock}: {e}"));
+            return 1;
+        }
+    }
+    match Command::new("tmux")
+        .args(["-L", &sock, "attach", "-t", session])
+        .status()
+    {
+        Ok(s) => s.code().unwrap_or(1),
+        Err(e) => {
+            eprintln_err(&format!("Failed to run tmux: {e}"));
+            1
+        }
+    }
+}
+
+/// Kill the tmux server behind `sock`, removing the socket if it was stale.
+fn kill_socket(sock: &str, force: bool) -> bool {
+    let sessions = match sessions_on(sock) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln_err(&format!("Not removing {sock}: {e}"));
+            return false;
+        }
+    };
+    if sessions.is_empty() {
+        let path = proc::tmux_socket_dir().join(sock);
+        if let Err(e) = std::fs::remove_file(&path) {
+            eprintln_err(&format!(
+                "Failed to remove stale socket {}: {e}",
+                path.display()
+            ));
+            return false;
+        }
+        eprintln!("removed stale socket {sock}");
+        return true;
+    }
+    if !force && sessions.iter().any(|s| s.recording.is_some()) {
+        eprintln_warn(&format!(
+            "{} is recording; stop the recording first or use --force.",
+            sock.trim_start_matches("ttyd-")
+        ));
+        return false;
+    }
+    match proc::run_tmux(&["-L", sock, "kill-server"]) {
+        Ok(()) => {
+            let _ = std::fs::remove_file(proc::tmux_socket_dir().join(sock));
+            eprintln!("killed {}", sock.trim_start_matches("ttyd-"));
+            true
+        }
+        Err(e) => {
+            eprintln_err(&format!("Failed to kill {sock}: {e}"));
+            false
+        }
+    }
+}
+
+fn run_kill(m: &ArgMatches) -> i32 {
+    let session = m.get_one::<String>("session").unwrap();
+    let sock = format!("ttyd-{session}");
+    if !proc::ttyd_sockets().contains(&sock) {
+        eprintln_err(&format!("No recording session named {session:?}"));
+        return 1;
+    }
+    if kill_socket(&sock, m.get_flag("force")) {
+        0
+    } else {
+        1
+    }
+}
+
+fn run_kill_all(m: &ArgMatches) -> i32 {
+    let force = m.get_flag("force");
+    let mut rc = 0;
+    for sock in proc::ttyd_sockets() {
+        if !kill_socket(&sock, force) {
+            rc = 1;
+        }
+    }
+    rc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_missing_server_is_stale() {
+        assert!(server_is_gone(
+            "no server running on /tmp/tmux-1000/ttyd-demo\n"
+        ));
+        assert!(server_is_gone(
+            "error connecting to /tmp/tmux-1000/ttyd-demo (Connection refused)\n"
+        ));
+        assert!(!server_is_gone(
+            "protocol version mismatch (client 8, server 7)\n"
+        ));
+        assert!(!server_is_gone(""));
+    }
+}
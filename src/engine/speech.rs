@@ -0,0 +1,287 @@
+use crate::engine::build;
+use crate::util::compile::{self, Project};
+use crate::util::human::human_size;
+use crate::util::scenescript::Timeline;
+use crate::util::speech::{self, Backend, Builder, CueAudio, Format, Options};
+use crate::util::{eprintln_err, timeline};
+use clap::ArgMatches;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn run_speech(m: &ArgMatches) -> i32 {
+    match m.subcommand() {
+        Some(("build", m)) => run_build(m),
+        Some(("say", m)) => run_say(m),
+        Some(("status", m)) => run_status(m),
+        Some(("gc", m)) => run_gc(m),
+        _ => 1,
+    }
+}
+
+/// The speech step the speech options ask for; `None` with `--no-speech`.
+pub fn options(m: &ArgMatches) -> Option<Options> {
+    if m.try_get_one::<bool>("no_speech").ok().flatten() == Some(&true) {
+        return None;
+    }
+    Some(Options {
+        backend: *m.get_one::<Backend>("tts").unwrap(),
+        format: *m.get_one::<Format>("format").unwrap(),
+        command: m.get_one::<String>("tts_command").cloned(),
+        voiceover: m.get_one::<PathBuf>("voiceover").cloned(),
+        pronunciation: m.get_one::<PathBuf>("pronunciation").cloned(),
+    })
+}
+
+/// `speech build`: synthesize the speech of compiled timelines.
+fn run_build(m: &ArgMatches) -> i32 {
+    let project = build::project(m);
+    let compiled = project.compiled();
+    let names = match m.get_many::<String>("names") {
+        Some(names) => names.cloned().collect(),
+        None => match timeline::compiled_names(&compiled) {
+            Ok(names) => names,
+            Err(_) => {
+                eprintln_err(&format!(
+                    "No compiled timelines in {} (run `shell-scene build --player {}`)",
+                    compiled.display(),
+                    project.player.display()
+                ));
+                return 1;
+            }
+        },
+    };
+    if synthesize(&project, &names, &options(m).unwrap()) {
+        0
+    } else {
+        1
+    }
+}
+
+/// `speech say`: print text as the pronunciation dictionary has it said.
+fn run_say(m: &ArgMatches) -> i32 {
+    let project = build::project(m);
+    let path = m.get_one::<PathBuf>("pronunciation").map(PathBuf::as_path);
+    let dict = match speech::dictionary(&project, path) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln_err(&e.to_string());
+            return 1;
+        }
+    };
+    let voice = m.get_one::<String>("voice").unwrap();
+    println!(
+        "{}",
+        dict.apply(m.get_one::<String>("text").unwrap(), voice)
+    );
+    0
+}
+
+/// Replace the speech cues of compiled timelines `names` with audio,
+/// reporting what it did. False if any timeline failed.
+pub fn synthesize(project: &Project, names: &[String], options: &Options) -> bool {
+    let mut builder = match Builder::new(project, options) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln_err(&format!("[speech] {e}"));
+            return false;
+        }
+    };
+    let mut ok = true;
+    for name in names {
+        if let Err(e) = builder.build(name) {
+            eprintln_err(&format!("[speech:{name}] {e}"));
+            ok = false;
+        }
+    }
+    eprintln!(
+        "[speech] {} clip(s) synthesized, {} cached, in {}",
+        builder.report.generated,
+        builder.report.cached,
+        project.public().join("speech").display()
+    );
+    ok
+}
+
+/// The `say` cues of a project's timelines and their audio.
+struct Survey {
+    project: Project,
+    cues: Vec<(String, Vec<CueAudio>)>,
+    /// Synthesized audio nothing uses.
+    orphans: Vec<PathBuf>,
+}
+
+/// `None` (after reporting why) unless every timeline parsed, as the cues
+/// of the others are unknown.
+fn survey(m: &ArgMatches) -> Option<Survey> {
+    let project = build::project(m);
+    if !project.timelines.is_dir() {
+        eprintln_err(&format!("Not found: {}", project.timelines.display()));
+        return None;
+    }
+    let builder = match Builder::new(&project, &options(m).unwrap()) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln_err(&e.to_string());
+            return None;
+        }
+    };
+    let mut ok = true;
+    let mut cues = Vec::new();
+    for tl in sources(&project.timelines, &mut ok) {
+        let audio = builder.audio(&tl.name, &Value::Array(tl.items));
+        cues.push((tl.name, audio));
+    }
+    if !ok {
+        return None;
+    }
+    // what the compiled timelines play is in use too, whatever made it;
+    // audio of other backends or formats that they don't play is not
+    let mut keep: BTreeSet<String> = cues
+        .iter()
+        .flat_map(|(_, audio)| audio.iter().filter_map(|a| a.src.clone()))
+        .collect();
+    let compiled = project.compiled();
+    for name in timeline::compiled_names(&compiled).unwrap_or_default() {
+        let json = fs::read(compiled.join(format!("{name}.json")))
+            .ok()
+            .and_then(|b| serde_json::from_slice::<Value>(&b).ok());
+        if let Some(json) = json {
+            keep.extend(speech::audio_srcs(&json["items"]).map(String::from));
+        }
+    }
+    let orphans = match speech::orphans(&project, &keep) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln_err(&format!(
+                "Failed to read {}: {e}",
+                project.public().join("speech").display()
+            ));
+            return None;
+        }
+    };
+    drop(builder);
+    Some(Survey {
+        project,
+        cues,
+        orphans,
+    })
+}
+
+/// The timelines in `dir`; clears `ok` for any that don't parse.
+fn sources(dir: &Path, ok: &mut bool) -> Vec<Timeline> {
+    let mut out = Vec::new();
+    for (file, parsed) in compile::read_timelines(dir) {
+        match parsed {
+            Ok(tl) => out.push(tl),
+            Err(e) => {
+                eprintln_err(&format!("{}: {e}", file.display()));
+                *ok = false;
+            }
+        }
+    }
+    out
+}
+
+fn total_size(files: &[PathBuf]) -> u64 {
+    files
+        .iter()
+        .filter_map(|f| fs::metadata(f).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// `speech status`: which cues have audio, and how much space it takes.
+fn run_status(m: &ArgMatches) -> i32 {
+    let Some(Survey {
+        project,
+        cues,
+        orphans,
+    }) = survey(m)
+    else {
+        return 1;
+    };
+    println!("{:<20} {:<8} {:>9}  TEXT", "TIMELINE", "STATUS", "SIZE");
+    let (mut count, mut missing, mut size) = (0, 0, 0);
+    for (name, audio) in &cues {
+        for a in audio {
+            count += 1;
+            size += a.size.unwrap_or(0);
+            missing += usize::from(a.size.is_none());
+            println!(
+                "{:<20} {:<8} {:>9}  {:?}",
+                name,
+                if a.size.is_some() {
+                    "cached"
+                } else {
+                    "missing"
+                },
+                a.size.map_or("-".into(), human_size),
+                a.text
+            );
+        }
+    }
+    eprintln!(
+        "{count} cue(s), {missing} missing; {} of audio in {}",
+        human_size(size),
+        project.public().join("speech").display()
+    );
+    if !orphans.is_empty() {
+        eprintln!(
+            "{} orphaned file(s) ({}); `speech gc --yes` removes them",
+            orphans.len(),
+            human_size(total_size(&orphans))
+        );
+    }
+    0
+}
+
+/// `speech gc`: remove synthesized audio no timeline uses anymore.
+fn run_gc(m: &ArgMatches) -> i32 {
+    let Some(Survey {
+        project, orphans, ..
+    }) = survey(m)
+    else {
+        return 1;
+    };
+    if orphans.is_empty() {
+        eprintln!("No orphaned speech audio.");
+        return 0;
+    }
+    let total = total_size(&orphans);
+    if !m.get_flag("yes") {
+        for f in &orphans {
+            let size = fs::metadata(f).map_or(0, |m| m.len());
+            println!("{}\t{}", human_size(size), f.display());
+        }
+        eprintln!(
+            "{} orphaned file(s) ({}). Re-run with --yes to delete them",
+            orphans.len(),
+            human_size(total)
+        );
+        return 0;
+    }
+    let mut rc = 0;
+    for f in &orphans {
+        match fs::remove_file(f) {
+            Ok(()) => eprintln!("removed {}", f.display()),
+            Err(e) => {
+                eprintln_err(&format!("Failed to remove {}: {e}", f.display()));
+                rc = 1;
+            }
+        }
+    }
+    // directories of timelines that are gone
+    let dirs: BTreeSet<&Path> = orphans.iter().filter_map(|f| f.parent()).collect();
+    for dir in dirs {
+        let _ = fs::remove_dir(dir);
+    }
+    eprintln!(
+        "Removed {} file(s) ({}) from {}",
+        orphans.len(),
+        human_size(total),
+        project.public().join("speech").display()
+    );
+    rc
+}
@@ -0,0 +1,443 @@
+use crate::util::{eprintln_err, eprintln_warn};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::ArgMatches;
+use portable_pty::{CommandBuilder, PtySize, native_pty_system};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use tungstenite::protocol::{Role, WebSocket};
+use tungstenite::{Message, handshake::derive_accept_key};
+
+const INDEX_HTML: &str = include_str!("../../assets/web-terminal/index.html");
+const ASSETS: [(&str, &str, &str); 4] = [
+    (
+        "/xterm.js",
+        "application/javascript",
+        include_str!("../../assets/web-terminal/xterm.js"),
+    ),
+    (
+        "/xterm.css",
+        "text/css",
+        include_str!("../../assets/web-terminal/xterm.css"),
+    ),
+    (
+        "/addon-fit.js",
+        "application/javascript",
+        include_str!("../../assets/web-terminal/addon-fit.js"),
+    ),
+    (
+        "/addon-webgl.js",
+        "application/javascript",
+        include_str!("../../assets/web-terminal/addon-webgl.js"),
+    ),
+];
+
+/// How long a WebSocket read waits before the PTY output is forwarded.
+const POLL: Duration = Duration::from_millis(10);
+
+struct Server {
+    command: Vec<String>,
+    /// Basic auth value (`base64(user:password)`), also the WebSocket token.
+    token: Option<String>,
+    index: String,
+    writable: bool,
+    once: bool,
+    busy: AtomicBool,
+}
+
+/// `web-terminal`: built-in replacement for ttyd. Serves an xterm.js page and
+/// runs the command in a PTY for each WebSocket client. It takes the subset of
+/// ttyd's options that `record` uses, so either can be spawned the same way.
+pub fn run_web_terminal(m: &ArgMatches) -> i32 {
+    let port = *m.get_one::<u16>("port").unwrap();
+    let bind = *m.get_one::<IpAddr>("interface").unwrap();
+    let options: Vec<&String> = m
+        .get_many::<String>("client_option")
+        .unwrap_or_default()
+        .collect();
+    let server = Arc::new(Server {
+        command: m.get_many::<String>("command").unwrap().cloned().collect(),
+        token: m.get_one::<String>("credential").map(|c| BASE64.encode(c)),
+        index: INDEX_HTML.replace("__OPTIONS__", &client_options_json(&options)),
+        writable: m.get_flag("writable"),
+        once: m.get_flag("once"),
+        busy: AtomicBool::new(false),
+    });
+
+    let listener = match TcpListener::bind((bind, port)) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln_err(&format!("Failed to listen on {bind}:{port}: {e}"));
+            return 1;
+        }
+    };
+    eprintln!("[web-terminal] Listening on {bind}:{port}");
+    for stream in listener.incoming().flatten() {
+        let server = server.clone();
+        thread::spawn(move || {
+            if let Err(e) = handle(&server, stream) {
+                log::debug!("web-terminal connection: {e}");
+            }
+        });
+    }
+    0
+}
+
+/// ttyd's `-t key=value` client options as a JSON object; values that parse
+/// as JSON (numbers, booleans, theme objects) are kept typed.
+fn client_options_json(options: &[&String]) -> String {
+    let mut map = Map::new();
+    for opt in options {
+        let Some((k, v)) = opt.split_once('=') else {
+            continue;
+        };
+        let value = serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.to_string()));
+        map.insert(k.to_string(), value);
+    }
+    // safe to inline in a <script> element
+    Value::Object(map).to_string().replace("</", "<\\/")
+}
+
+struct Request {
+    path: String,
+    headers: HashMap<String, String>,
+    /// Bytes read past the end of the request head.
+    rest: Vec<u8>,
+}
+
+fn read_request(stream: &mut TcpStream) -> io::Result<Request> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let end = loop {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed",
+            ));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        if buf.len() > 16 * 1024 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+    };
+    let head = String::from_utf8_lossy(&buf[..end]);
+    let mut lines = head.lines();
+    let path = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .split('?')
+        .next()
+        .unwrap_or("/")
+        .to_string();
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+    Ok(Request {
+        path,
+        headers,
+        rest: buf[end..].to_vec(),
+    })
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (k, v) in headers {
+        head.push_str(&format!("{k}: {v}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body)
+}
+
+fn handle(server: &Server, mut stream: TcpStream) -> io::Result<()> {
+    let req = read_request(&mut stream)?;
+    if req.path == "/ws" {
+        return serve_terminal(server, stream, req);
+    }
+
+    if let Some(token) = &server.token
+        && req.headers.get("authorization") != Some(&format!("Basic {token}"))
+    {
+        return respond(
+            &mut stream,
+            "401 Unauthorized",
+            &[("WWW-Authenticate", "Basic realm=\"shell-scene\"")],
+            b"",
+        );
+    }
+    match req.path.as_str() {
+        "/" => respond(
+            &mut stream,
+            "200 OK",
+            &[("Content-Type", "text/html; charset=utf-8")],
+            server.index.as_bytes(),
+        ),
+        "/token" => {
+            let body = serde_json::json!({ "token": server.token.as_deref().unwrap_or("") });
+            respond(
+                &mut stream,
+                "200 OK",
+                &[("Content-Type", "application/json")],
+                body.to_string().as_bytes(),
+            )
+        }
+        path => match ASSETS.iter().find(|(p, _, _)| *p == path) {
+            Some((_, mime, body)) => respond(
+                &mut stream,
+                "200 OK",
+                &[("Content-Type", mime)],
+                body.as_bytes(),
+            ),
+            None => respond(&mut stream, "404 Not Found", &[], b""),
+        },
+    }
+}
+
+/// Upgrade to a WebSocket and run the command in a PTY until either side ends.
+fn serve_terminal(server: &Server, mut stream: TcpStream, req: Request) -> io::Result<()> {
+    let Some(key) = req.headers.get("sec-websocket-key") else {
+        return respond(&mut stream, "400 Bad Request", &[], b"");
+    };
+    // without a credential the token is empty, so any page open in the
+    // presenter's browser could otherwise connect and type
+    if !same_origin(&req.headers) {
+        return respond(&mut stream, "403 Forbidden", &[], b"");
+    }
+    if server.once && server.busy.load(Ordering::SeqCst) {
+        return respond(&mut stream, "503 Service Unavailable", &[], b"");
+    }
+    let accept = derive_accept_key(key.as_bytes());
+    stream.write_all(
+        format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+        )
+        .as_bytes(),
+    )?;
+    let mut ws = WebSocket::from_partially_read(stream, req.rest, Role::Server, None);
+
+    // only an authenticated client takes the `--once` slot
+    let Some(hello) = authenticate(server, &mut ws)? else {
+        let _ = ws.close(None);
+        return ws.flush().or(Ok(()));
+    };
+    if server.once && server.busy.swap(true, Ordering::SeqCst) {
+        let _ = ws.close(None);
+        return ws.flush().or(Ok(()));
+    }
+    let rc = run_session(server, &mut ws, &hello);
+    let _ = ws.close(None);
+    let _ = ws.flush();
+    if server.once {
+        // like `ttyd --once`: exit when the client goes away
+        std::process::exit(if rc.is_ok() { 0 } else { 1 });
+    }
+    rc
+}
+
+/// True unless a browser says the request comes from a page on another
+/// host: the `Origin` header, if there is one, must name the `Host`.
+fn same_origin(headers: &HashMap<String, String>) -> bool {
+    let Some(origin) = headers.get("origin") else {
+        return true;
+    };
+    let Some(host) = headers.get("host") else {
+        return false;
+    };
+    origin
+        .split_once("://")
+        .is_some_and(|(_, rest)| rest.eq_ignore_ascii_case(host))
+}
+
+/// Read the client's first message, which authenticates it and sizes the
+/// terminal. `None` if its token is wrong.
+fn authenticate(server: &Server, ws: &mut WebSocket<TcpStream>) -> io::Result<Option<Value>> {
+    let hello: Value = match ws.read().map_err(io::Error::other)? {
+        Message::Text(t) => serde_json::from_str(t.as_str()).unwrap_or_default(),
+        Message::Binary(b) => serde_json::from_slice(&b).unwrap_or_default(),
+        _ => Value::Null,
+    };
+    let token = hello["AuthToken"].as_str().unwrap_or("");
+    if server.token.as_deref().is_some_and(|t| t != token) {
+        eprintln_warn("[web-terminal] Rejected a client with a bad token.");
+        return Ok(None);
+    }
+    Ok(Some(hello))
+}
+
+fn run_session(server: &Server, ws: &mut WebSocket<TcpStream>, hello: &Value) -> io::Result<()> {
+    let pty = native_pty_system()
+        .openpty(pty_size(hello).unwrap_or_default())
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let (program, args) = server
+        .command
+        .split_first()
+        .ok_or_else(|| io::Error::other("no command"))?;
+    let mut cmd = CommandBuilder::new(program);
+    cmd.args(args);
+    cmd.env("TERM", "xterm-256color");
+    cmd.cwd(std::env::current_dir()?);
+    let mut child = pty
+        .slave
+        .spawn_command(cmd)
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    drop(pty.slave);
+
+    let mut reader = pty
+        .master
+        .try_clone_reader()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let mut writer = pty
+        .master
+        .take_writer()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        while let Ok(n) = reader.read(&mut buf) {
+            if n == 0 || tx.send(buf[..n].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    ws.get_mut().set_read_timeout(Some(POLL))?;
+    let result = loop {
+        match ws.read() {
+            Ok(Message::Text(t)) => {
+                let t = t.as_str();
+                match t.as_bytes().first() {
+                    Some(b'0') if server.writable => writer.write_all(&t.as_bytes()[1..])?,
+                    Some(b'1') => {
+                        let size = serde_json::from_str(&t[1..])
+                            .ok()
+                            .and_then(|v| pty_size(&v));
+                        if let Some(size) = size {
+                            let _ = pty.master.resize(size);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Message::Close(_)) => break Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                break Ok(());
+            }
+            Err(e) => break Err(io::Error::other(e)),
+        }
+
+        let mut out = Vec::new();
+        let ended = loop {
+            match rx.try_recv() {
+                Ok(chunk) => out.extend(chunk),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        if !out.is_empty()
+            && let Err(e) = ws.send(Message::binary(out))
+        {
+            break Err(io::Error::other(e));
+        }
+        if ended {
+            break Ok(());
+        }
+    };
+
+    if let Ok(None) = child.try_wait() {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+    result
+}
+
+fn pty_size(v: &Value) -> Option<PtySize> {
+    Some(PtySize {
+        cols: v["columns"].as_u64()?.try_into().ok()?,
+        rows: v["rows"].as_u64()?.try_into().ok()?,
+        pixel_width: 0,
+        pixel_height: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_options_keep_json_types() {
+        let opts = [
+            "fontSize=24".to_string(),
+            "fontFamily=Fira Code".to_string(),
+            r##"theme={"background":"#121314"}"##.to_string(),
+            "titleFixed=</script>".to_string(),
+        ];
+        let refs: Vec<&String> = opts.iter().collect();
+        assert_eq!(
+            client_options_json(&refs),
+            r##"{"fontSize":24,"fontFamily":"Fira Code","theme":{"background":"#121314"},"titleFixed":"<\/script>"}"##
+        );
+    }
+
+    #[test]
+    fn websockets_only_from_the_same_origin() {
+        let headers = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let host = ("host", "127.0.0.1:7681");
+        assert!(same_origin(&headers(&[
+            host,
+            ("origin", "http://127.0.0.1:7681")
+        ])));
+        assert!(same_origin(&headers(&[
+            host,
+            ("origin", "https://127.0.0.1:7681")
+        ])));
+        assert!(same_origin(&headers(&[host])));
+        assert!(!same_origin(&headers(&[
+            host,
+            ("origin", "https://evil.example")
+        ])));
+        assert!(!same_origin(&headers(&[
+            host,
+            ("origin", "http://127.0.0.1:8000")
+        ])));
+        assert!(!same_origin(&headers(&[host, ("origin", "null")])));
+        assert!(!same_origin(&headers(&[(
+            "origin",
+            "http://127.0.0.1:7681"
+        )])));
+    }
+}
@@ -21,6 +21,15 @@ fn main() {
     let exit_code = match matches.subcommand() {
         Some(("record", m)) => engine::record::run_record(m),
         Some(("record-hook", m)) => engine::record::run_record_hook(m),
+        Some(("stage", m)) => engine::record::run_stage(m),
+        Some(("casts", m)) => engine::casts::run_casts(m),
+        Some(("sessions", m)) => engine::sessions::run_sessions(m),
+        Some(("build", m)) => engine::build::run_build(m),
+        Some(("speech", m)) => engine::speech::run_speech(m),
+        Some(("publish", m)) => engine::publish::run_publish(m),
+        Some(("serve", m)) => engine::serve::run_serve(m),
+        #[cfg(feature = "web-terminal")]
+        Some(("web-terminal", m)) => engine::web_terminal::run_web_terminal(m),
         Some(("completions", m)) => {
             if let Some(shell) = m.get_one::<String>("shell") {
                 let sh = match shell.as_str() {
@@ -0,0 +1,163 @@
+use crate::util::{deps, eprintln_warn};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+/// Browsers tried for `--app-window` when no `--browser` is given.
+const APP_BROWSERS: [&str; 6] = [
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "brave-browser",
+    "firefox",
+];
+
+/// How to show the recording UI once ttyd is up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Open {
+    No,
+    /// `xdg-open`, or the given browser command.
+    Browser(Option<String>),
+    /// A separate browser window sized to the terminal.
+    AppWindow {
+        browser: Option<String>,
+        width: u32,
+        height: u32,
+    },
+}
+
+/// Approximate window size (in CSS pixels) for a `cols`x`rows` xterm.js
+/// terminal: monospace cells are about 0.6em wide and 1.2em tall, plus
+/// ttyd's padding and the window title bar.
+pub fn window_size(cols: u32, rows: u32, font_size: u32, line_height: f64) -> (u32, u32) {
+    const PADDING: f64 = 16.0;
+    const TITLE_BAR: f64 = 40.0;
+    let font = font_size as f64;
+    let width = cols as f64 * font * 0.6 + PADDING;
+    let height = rows as f64 * font * 1.2 * line_height + PADDING + TITLE_BAR;
+    (width.ceil() as u32, height.ceil() as u32)
+}
+
+/// Open `url`. Returns the app window's process so it can be closed when
+/// the recording ends; tabs in an existing browser are left alone.
+pub fn open(how: &Open, url: &str) -> Option<Child> {
+    match how {
+        Open::No => None,
+        Open::Browser(browser) => {
+            let mut argv = match browser {
+                Some(cmd) => split_cmd(cmd),
+                None if deps::have("xdg-open") => vec!["xdg-open".to_string()],
+                None => {
+                    eprintln_warn("'xdg-open' not found; not opening a browser automatically.");
+                    return None;
+                }
+            };
+            argv.push(url.to_string());
+            let _ = spawn(&argv);
+            None
+        }
+        Open::AppWindow {
+            browser,
+            width,
+            height,
+        } => {
+            let base = match browser {
+                Some(cmd) if !cmd.trim().is_empty() => split_cmd(cmd),
+                _ => match APP_BROWSERS.iter().find(|b| deps::have(b)) {
+                    Some(b) => vec![b.to_string()],
+                    None => {
+                        eprintln_warn(&format!(
+                            "No browser for --app-window found (tried {}); use --browser CMD.",
+                            APP_BROWSERS.join(", ")
+                        ));
+                        return None;
+                    }
+                },
+            };
+            let firefox = base[0].contains("firefox");
+            let profile = profile_dir(if firefox { "firefox" } else { "chromium" });
+            spawn(&app_window_argv(&base, &profile, url, *width, *height))
+        }
+    }
+}
+
+/// Browser command line for an app window: `--app` for Chromium and friends,
+/// a new sized instance for Firefox. Both use their own profile so the window
+/// never joins the presenter's personal browser session.
+fn app_window_argv(
+    base: &[String],
+    profile: &Path,
+    url: &str,
+    width: u32,
+    height: u32,
+) -> Vec<String> {
+    let mut argv = base.to_vec();
+    if base[0].contains("firefox") {
+        argv.extend([
+            "--new-instance".to_string(),
+            "--profile".to_string(),
+            profile.to_string_lossy().to_string(),
+            "--width".to_string(),
+            width.to_string(),
+            "--height".to_string(),
+            height.to_string(),
+            url.to_string(),
+        ]);
+    } else {
+        argv.extend([
+            format!("--user-data-dir={}", profile.display()),
+            format!("--window-size={width},{height}"),
+            "--no-first-run".to_string(),
+            format!("--app={url}"),
+        ]);
+    }
+    argv
+}
+
+fn profile_dir(kind: &str) -> PathBuf {
+    let dir = dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("shell-scene")
+        .join(format!("{kind}-profile"));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+fn split_cmd(cmd: &str) -> Vec<String> {
+    cmd.split_whitespace().map(String::from).collect()
+}
+
+fn spawn(argv: &[String]) -> Option<Child> {
+    let (program, args) = argv.split_first()?;
+    match Command::new(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => Some(child),
+        Err(e) => {
+            eprintln_warn(&format!("Failed to open a browser with {program}: {e}"));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_window_commands() {
+        assert_eq!(window_size(80, 24, 24, 1.0), (1168, 748));
+        let url = "http://127.0.0.1:7681/";
+        let profile = Path::new("/tmp/profile");
+        let chromium = app_window_argv(&["chromium".into()], profile, url, 1168, 748);
+        assert_eq!(chromium[0], "chromium");
+        assert!(chromium.contains(&"--window-size=1168,748".to_string()));
+        assert_eq!(chromium.last().unwrap(), "--app=http://127.0.0.1:7681/");
+        let firefox = app_window_argv(&["firefox".into()], profile, url, 1168, 748);
+        assert!(firefox.windows(2).any(|w| w == ["--width", "1168"]));
+        assert_eq!(firefox.last().unwrap(), url);
+    }
+}
@@ -0,0 +1,415 @@
+use serde_json::{Map, Value, json};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Summary of an asciicast v2 file, as much as can be read from it.
+#[derive(Debug, Clone, Default)]
+pub struct CastInfo {
+    /// Header `version`, if present.
+    pub version: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Unix timestamp from the header, if present.
+    pub timestamp: Option<i64>,
+    /// Header `duration` if present, otherwise the time of the last event.
+    pub duration: f64,
+    pub events: usize,
+    /// Marker events (`[t, "m", label]`) as (time, label).
+    pub markers: Vec<(f64, String)>,
+}
+
+pub fn read_info(path: &Path) -> io::Result<CastInfo> {
+    let bytes = fs::read(path)?;
+    Ok(parse_info(&String::from_utf8_lossy(&bytes)))
+}
+
+pub fn parse_info(txt: &str) -> CastInfo {
+    let mut info = CastInfo::default();
+    let mut lines = txt.lines().filter(|l| !l.trim().is_empty());
+
+    let mut header_duration = None;
+    if let Some(Ok(Value::Object(h))) = lines.next().map(serde_json::from_str::<Value>) {
+        info.version = h.get("version").and_then(Value::as_u64);
+        info.width = h.get("width").and_then(Value::as_u64).map(|v| v as u32);
+        info.height = h.get("height").and_then(Value::as_u64).map(|v| v as u32);
+        info.timestamp = h.get("timestamp").and_then(Value::as_i64);
+        header_duration = h.get("duration").and_then(Value::as_f64);
+    }
+
+    let mut last = 0.0;
+    for line in lines {
+        if let Some(t) = event_time(line) {
+            last = t;
+            info.events += 1;
+            info.markers.extend(marker_label(line).map(|l| (t, l)));
+        }
+    }
+    info.duration = header_duration.unwrap_or(last);
+    info
+}
+
+/// Time (seconds) of an event line like `[1.23, "o", "..."]`.
+pub fn event_time(line: &str) -> Option<f64> {
+    match serde_json::from_str::<Value>(line).ok()? {
+        Value::Array(a) => a.first().and_then(Value::as_f64),
+        _ => None,
+    }
+}
+
+/// Label of a marker event line like `[1.23, "m", "label"]`.
+fn marker_label(line: &str) -> Option<String> {
+    if !line.contains("\"m\"") {
+        return None;
+    }
+    match serde_json::from_str::<Value>(line).ok()? {
+        Value::Array(a) if a.get(1)? == "m" => a.get(2)?.as_str().map(String::from),
+        _ => None,
+    }
+}
+
+/// A problem found in a cast file. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    MissingHeader,
+    HeaderField(&'static str),
+    InvalidUtf8 { line: usize },
+    InvalidEvent { line: usize },
+    Truncated { line: usize },
+    NonMonotonic { line: usize, time: f64, prev: f64 },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingHeader => write!(f, "missing asciicast header"),
+            Issue::HeaderField(k) => write!(f, "header is missing or has an invalid {k:?}"),
+            Issue::InvalidUtf8 { line } => write!(f, "line {line}: invalid UTF-8"),
+            Issue::InvalidEvent { line } => write!(f, "line {line}: not a valid event"),
+            Issue::Truncated { line } => write!(f, "line {line}: truncated event"),
+            Issue::NonMonotonic { line, time, prev } => {
+                write!(f, "line {line}: timestamp {time} is before {prev}")
+            }
+        }
+    }
+}
+
+/// Result of [`repair`]: the fixed cast text plus everything that was wrong.
+#[derive(Debug, Clone)]
+pub struct Repaired {
+    pub text: String,
+    pub issues: Vec<Issue>,
+}
+
+/// Check a cast without changing it.
+pub fn check(bytes: &[u8]) -> Vec<Issue> {
+    repair(bytes, 80, 24).issues
+}
+
+/// Rebuild a valid asciicast v2 from `bytes`: drop broken and truncated
+/// lines, clamp timestamps so they never go backwards, replace invalid
+/// UTF-8, and fill in missing header fields (`cols`/`rows` are used when the
+/// header has no size).
+pub fn repair(bytes: &[u8], cols: u32, rows: u32) -> Repaired {
+    let mut issues = Vec::new();
+    let mut header: Option<String> = None;
+    let mut events: Vec<String> = Vec::new();
+    let mut prev = 0.0_f64;
+
+    let segments: Vec<&[u8]> = bytes.split(|b| *b == b'\n').collect();
+    for (i, seg) in segments.iter().enumerate() {
+        let line_no = i + 1;
+        let (line, mut changed) = match std::str::from_utf8(seg) {
+            Ok(s) => (s.to_string(), false),
+            Err(_) => {
+                issues.push(Issue::InvalidUtf8 { line: line_no });
+                (String::from_utf8_lossy(seg).into_owned(), true)
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fixed = fix_lone_surrogates(&line);
+        if fixed != line && !changed {
+            issues.push(Issue::InvalidUtf8 { line: line_no });
+            changed = true;
+        }
+
+        let parsed = serde_json::from_str::<Value>(&fixed);
+        // JSON that ends early (an open array or string) was cut off mid-write,
+        // wherever it is: `--append` continues after a truncated last line
+        let truncated = parsed.as_ref().is_err_and(serde_json::Error::is_eof);
+        let value = parsed.ok();
+        if header.is_none() {
+            if let Some(Value::Object(h)) = value {
+                let (h, fixed_header) = repair_header(h, cols, rows, &mut issues);
+                header = Some(if fixed_header || changed {
+                    Value::Object(h).to_string()
+                } else {
+                    line
+                });
+                continue;
+            }
+            issues.push(Issue::MissingHeader);
+            let (h, _) = repair_header(Map::new(), cols, rows, &mut Vec::new());
+            header = Some(Value::Object(h).to_string());
+        }
+
+        let Some((time, code, data)) = value.as_ref().and_then(parse_event) else {
+            issues.push(if truncated {
+                Issue::Truncated { line: line_no }
+            } else {
+                Issue::InvalidEvent { line: line_no }
+            });
+            continue;
+        };
+        let time = if time < prev {
+            issues.push(Issue::NonMonotonic {
+                line: line_no,
+                time,
+                prev,
+            });
+            changed = true;
+            prev
+        } else {
+            time
+        };
+        prev = time;
+        events.push(if changed {
+            json!([time, code, data]).to_string()
+        } else {
+            line
+        });
+    }
+
+    let mut text = header.unwrap_or_else(|| {
+        issues.push(Issue::MissingHeader);
+        let (h, _) = repair_header(Map::new(), cols, rows, &mut Vec::new());
+        Value::Object(h).to_string()
+    });
+    text.push('\n');
+    for e in events {
+        text.push_str(&e);
+        text.push('\n');
+    }
+    Repaired { text, issues }
+}
+
+fn repair_header(
+    mut h: Map<String, Value>,
+    cols: u32,
+    rows: u32,
+    issues: &mut Vec<Issue>,
+) -> (Map<String, Value>, bool) {
+    let mut changed = false;
+    if h.get("version").and_then(Value::as_u64) != Some(2) {
+        issues.push(Issue::HeaderField("version"));
+        h.insert("version".into(), json!(2));
+        changed = true;
+    }
+    for (key, default) in [("width", cols), ("height", rows)] {
+        if h.get(key).and_then(Value::as_u64).is_none_or(|v| v == 0) {
+            issues.push(Issue::HeaderField(key));
+            h.insert(key.into(), json!(default));
+            changed = true;
+        }
+    }
+    (h, changed)
+}
+
+fn parse_event(v: &Value) -> Option<(f64, &str, &str)> {
+    let a = v.as_array()?;
+    if a.len() != 3 {
+        return None;
+    }
+    let t = a[0].as_f64().filter(|t| t.is_finite() && *t >= 0.0)?;
+    Some((t, a[1].as_str()?, a[2].as_str()?))
+}
+
+/// Remove the paused `intervals` (seconds, sorted, non-overlapping) from a
+/// cast: events inside a pause are dropped and later events are shifted back
+/// so playback runs straight through.
+pub fn cut_intervals(txt: &str, intervals: &[(f64, f64)]) -> String {
+    let mut out = String::with_capacity(txt.len());
+    let mut lines = txt.lines();
+    if let Some(header) = lines.next() {
+        out.push_str(header);
+        out.push('\n');
+    }
+    for line in lines {
+        let value = serde_json::from_str::<Value>(line).ok();
+        let Some((t, code, data)) = value.as_ref().and_then(parse_event) else {
+            continue;
+        };
+        if intervals.iter().any(|(a, b)| t >= *a && t < *b) {
+            continue;
+        }
+        let cut: f64 = intervals
+            .iter()
+            .filter(|(_, b)| *b <= t)
+            .map(|(a, b)| b - a)
+            .sum();
+        if cut == 0.0 {
+            out.push_str(line);
+        } else {
+            let t = ((t - cut) * 1e6).round() / 1e6;
+            out.push_str(&json!([t, code, data]).to_string());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Replace `\uD800`-`\uDFFF` escapes that are not part of a surrogate pair
+/// with `�`. These appear when a multi-byte character was split across
+/// two output chunks, and make the whole line unparseable.
+fn fix_lone_surrogates(line: &str) -> String {
+    fn escape_at(b: &[u8], i: usize) -> Option<u16> {
+        if b.get(i) == Some(&b'\\') && b.get(i + 1) == Some(&b'u') {
+            let hex = std::str::from_utf8(b.get(i + 2..i + 6)?).ok()?;
+            u16::from_str_radix(hex, 16).ok()
+        } else {
+            None
+        }
+    }
+    let b = line.as_bytes();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+    let mut start = 0;
+    while i < b.len() {
+        if b[i] == b'\\' {
+            match escape_at(b, i) {
+                Some(0xD800..=0xDBFF) if matches!(escape_at(b, i + 6), Some(0xDC00..=0xDFFF)) => {
+                    i += 12;
+                }
+                Some(0xD800..=0xDFFF) => {
+                    out.push_str(&line[start..i]);
+                    out.push_str("\\ufffd");
+                    i += 6;
+                    start = i;
+                }
+                // skip the escaped character so `\\u` isn't read as an escape
+                _ => i += 2,
+            }
+        } else {
+            i += 1;
+        }
+    }
+    out.push_str(&line[start.min(line.len())..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_from_last_event() {
+        let txt = "{\"version\": 2, \"width\": 80, \"height\": 24, \"timestamp\": 1700000000}\n\
+                   [0.5, \"o\", \"a\"]\n\
+                   [2.25, \"o\", \"b\"]\n";
+        let info = parse_info(txt);
+        assert_eq!(info.width, Some(80));
+        assert_eq!(info.height, Some(24));
+        assert_eq!(info.timestamp, Some(1700000000));
+        assert_eq!(info.events, 2);
+        assert_eq!(info.duration, 2.25);
+    }
+
+    #[test]
+    fn markers_are_listed() {
+        let txt = "{\"version\": 2, \"width\": 80, \"height\": 24}\n\
+                   [0.5, \"o\", \"\\\"m\\\"\"]\n\
+                   [1.0, \"m\", \"setup done\"]\n\
+                   [2.25, \"m\", \"\"]\n";
+        let info = parse_info(txt);
+        assert_eq!(info.events, 3);
+        assert_eq!(
+            info.markers,
+            [(1.0, "setup done".to_string()), (2.25, String::new())]
+        );
+    }
+
+    #[test]
+    fn duration_prefers_header() {
+        let txt = "{\"version\": 2, \"width\": 80, \"height\": 24, \"duration\": 9.5}\n[1.0, \"o\", \"a\"]\n";
+        assert_eq!(parse_info(txt).duration, 9.5);
+    }
+
+    #[test]
+    fn clean_cast_is_unchanged() {
+        let txt = "{\"version\": 2, \"width\": 80, \"height\": 24}\n[0.5, \"o\", \"a\"]\n";
+        let r = repair(txt.as_bytes(), 80, 24);
+        assert!(r.issues.is_empty(), "{:?}", r.issues);
+        assert_eq!(r.text, txt);
+    }
+
+    #[test]
+    fn repairs_killed_recording() {
+        let txt = "{\"version\": 2, \"height\": 24}\n\
+                   [1.0, \"o\", \"a\"]\n\
+                   [0.5, \"o\", \"b\\ud83d\"]\n\
+                   [2.0, \"o\", \"tru";
+        let r = repair(txt.as_bytes(), 100, 30);
+        assert_eq!(
+            r.issues,
+            vec![
+                Issue::HeaderField("width"),
+                Issue::InvalidUtf8 { line: 3 },
+                Issue::NonMonotonic {
+                    line: 3,
+                    time: 0.5,
+                    prev: 1.0
+                },
+                Issue::Truncated { line: 4 },
+            ]
+        );
+        assert!(check(r.text.as_bytes()).is_empty());
+        let info = parse_info(&r.text);
+        assert_eq!(info.width, Some(100));
+        assert_eq!(info.events, 2);
+        assert_eq!(info.duration, 1.0);
+    }
+
+    #[test]
+    fn cut_pauses() {
+        let txt = "{\"version\": 2, \"width\": 80, \"height\": 24}\n\
+                   [1.0, \"o\", \"a\"]\n\
+                   [2.5, \"o\", \"setup\"]\n\
+                   [5.0, \"o\", \"b\"]\n\
+                   [9.5, \"o\", \"c\"]\n";
+        let out = cut_intervals(txt, &[(2.0, 4.0), (9.0, f64::INFINITY)]);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "[1.0, \"o\", \"a\"]");
+        assert_eq!(event_time(lines[2]), Some(3.0));
+    }
+
+    #[test]
+    fn truncated_lines_are_found_by_content() {
+        let txt = "{\"version\": 2, \"width\": 80, \"height\": 24}\n\
+                   [1.0, \"o\", \"cut\n\
+                   [2.0, \"o\", \"a\"]\n\
+                   [2.5, \"o\"]\n\
+                   [3.0, \"o\", \"b\"\n";
+        let r = repair(txt.as_bytes(), 80, 24);
+        assert_eq!(
+            r.issues,
+            vec![
+                Issue::Truncated { line: 2 },
+                Issue::InvalidEvent { line: 4 },
+                Issue::Truncated { line: 5 },
+            ]
+        );
+        assert_eq!(parse_info(&r.text).events, 1);
+        assert!(check(r.text.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn missing_header_is_added() {
+        let r = repair(b"[0.1, \"o\", \"a\"]\n", 80, 24);
+        assert_eq!(r.issues, vec![Issue::MissingHeader]);
+        assert_eq!(parse_info(&r.text).events, 1);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::util::scenescript::{self, Timeline};
+use crate::util::{cast, fsx, timeline};
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The directories a timeline build reads and writes.
+#[derive(Debug, Clone)]
+pub struct Project {
+    /// The player project; output goes to its `public/`.
+    pub player: PathBuf,
+    /// Searched (recursively) for `.timeline` files.
+    pub timelines: PathBuf,
+    /// Where `record` puts casts; a fallback for cast paths that don't
+    /// resolve next to their timeline.
+    pub casts: PathBuf,
+}
+
+impl Project {
+    pub fn public(&self) -> PathBuf {
+        self.player.join("public")
+    }
+
+    /// Compiled timelines (`NAME.json`, `NAME.speech.js`, `registry.json`).
+    pub fn compiled(&self) -> PathBuf {
+        self.public().join("timelines")
+    }
+}
+
+/// What a build wrote, and what it couldn't.
+#[derive(Debug, Default)]
+pub struct Build {
+    pub names: Vec<String>,
+    pub casts: usize,
+    /// One message per problem; the timelines they concern weren't written.
+    pub problems: Vec<String>,
+}
+
+/// Compile every `.timeline` in the project for the player. Casts are
+/// resolved relative to their timeline and copied to `public/casts/` under
+/// the hash of their content, so compiled timelines don't depend on where the
+/// project lives. Each cast item also gets the cast's duration, size and
+/// markers, so the player can lay out a presentation before fetching casts.
+/// With `only`, just those `.timeline` files are compiled; the others keep
+/// what an earlier build wrote.
+pub fn build(project: &Project, only: Option<&BTreeSet<PathBuf>>) -> io::Result<Build> {
+    let compiled = project.compiled();
+    let cast_dir = project.public().join("casts");
+    fs::create_dir_all(&compiled)?;
+    fs::create_dir_all(&cast_dir)?;
+
+    let mut out = Build::default();
+    // every compiled timeline, built now or before
+    let mut registered = Vec::new();
+    let mut hashes = BTreeSet::new();
+    for (file, parsed) in read_timelines(&project.timelines) {
+        let tl = match parsed {
+            Ok(tl) => tl,
+            Err(e) => {
+                out.problems.push(format!("{}: {e}", file.display()));
+                continue;
+            }
+        };
+        if let Err(e) = check_name(&tl.name) {
+            out.problems.push(format!("{}: {e}", file.display()));
+            continue;
+        }
+        if registered.contains(&tl.name) {
+            out.problems.push(format!(
+                "{}: duplicate timeline name {:?}",
+                file.display(),
+                tl.name
+            ));
+            continue;
+        }
+        if only.is_some_and(|only| !only.contains(&file)) {
+            if compiled.join(format!("{}.json", tl.name)).is_file() {
+                registered.push(tl.name);
+            }
+            continue;
+        }
+
+        let mut json = tl.to_json();
+        let mut missing = Vec::new();
+        for item in json["items"].as_array_mut().into_iter().flatten() {
+            let Some(path) = item
+                .get("castPath")
+                .and_then(Value::as_str)
+                .filter(|_| item["type"] == "cast")
+            else {
+                continue;
+            };
+            let Some(src) = find_cast(&file, path, &project.casts) else {
+                missing.push(path.to_string());
+                continue;
+            };
+            let bytes = fs::read(&src)?;
+            let hash = fsx::short_hash(&bytes);
+            let dest = cast_dir.join(format!("{hash}.cast"));
+            if !dest.is_file() {
+                fs::write(&dest, &bytes)?;
+            }
+            item["castPath"] = format!("casts/{hash}.cast").into();
+            add_cast_info(item, &cast::parse_info(&String::from_utf8_lossy(&bytes)));
+            hashes.insert(hash);
+        }
+        if !missing.is_empty() {
+            for path in missing {
+                out.problems
+                    .push(format!("{}: cast {path} not found", file.display()));
+            }
+            continue;
+        }
+
+        write_compiled(&compiled, &tl.name, &json["items"])?;
+        registered.push(tl.name.clone());
+        out.names.push(tl.name);
+    }
+
+    let registry = serde_json::to_string_pretty(&timeline::registry(&registered))?;
+    fs::write(compiled.join("registry.json"), registry)?;
+    out.casts = hashes.len();
+    // with every timeline built, copies nothing uses anymore can go
+    if only.is_none() && out.problems.is_empty() {
+        prune_casts(&cast_dir, &hashes)?;
+    }
+    Ok(out)
+}
+
+fn add_cast_info(item: &mut Value, info: &cast::CastInfo) {
+    // anything else (say an asciicast v1 file) has no duration to read, and
+    // the player measures it itself
+    if info.version == Some(2) || info.events > 0 {
+        item["duration"] = json!(info.duration);
+    }
+    if let Some(w) = info.width {
+        item["width"] = json!(w);
+    }
+    if let Some(h) = info.height {
+        item["height"] = json!(h);
+    }
+    item["markers"] = info
+        .markers
+        .iter()
+        .map(|(time, label)| json!({ "time": time, "label": label }))
+        .collect();
+}
+
+/// Every `.timeline` file under `dir`, with the timeline it parses to or
+/// why it doesn't.
+pub fn read_timelines(dir: &Path) -> Vec<(PathBuf, Result<Timeline, String>)> {
+    timeline::find_timeline_files(dir)
+        .into_iter()
+        .map(|file| {
+            let parsed = fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|src| scenescript::parse(&src).map_err(|e| e.to_string()));
+            (file, parsed)
+        })
+        .collect()
+}
+
+/// Write `NAME.json` and `NAME.speech.js` for the player.
+pub fn write_compiled(compiled: &Path, name: &str, items: &Value) -> io::Result<()> {
+    check_name(name)?;
+    let json = json!({ "name": name, "items": items });
+    fs::write(
+        compiled.join(format!("{name}.json")),
+        serde_json::to_string_pretty(&json)?,
+    )?;
+    fs::write(
+        compiled.join(format!("{name}.speech.js")),
+        timeline::speech_module(name, items),
+    )
+}
+
+/// Timeline names become file names, so they can't leave the directory.
+pub fn check_name(name: &str) -> io::Result<()> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid timeline name {name:?} (no path separators or `..`)"),
+        ));
+    }
+    Ok(())
+}
+
+/// The file a timeline's `cast PATH` line refers to: `PATH` relative to the
+/// timeline (or absolute), else a cast of that name in the cast directory,
+/// as older timelines named casts by the URL they were served at.
+fn find_cast(timeline: &Path, path: &str, casts: &Path) -> Option<PathBuf> {
+    cast_candidates(timeline, path, casts)
+        .into_iter()
+        .find(|p| p.is_file())
+}
+
+/// Where `cast PATH` in `timeline` may live, in the order it is looked up:
+/// next to the timeline, then in `casts` by file name.
+pub fn cast_candidates(timeline: &Path, path: &str, casts: &Path) -> Vec<PathBuf> {
+    let mut out = vec![timeline.parent().unwrap_or(Path::new(".")).join(path)];
+    if let Some(name) = Path::new(path).file_name() {
+        out.push(casts.join(name));
+    }
+    out
+}
+
+fn prune_casts(dir: &Path, keep: &BTreeSet<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(stem) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|_| path.extension().is_some_and(|e| e == "cast"))
+        else {
+            continue;
+        };
+        let hashed = stem.len() == 16 && stem.bytes().all(|b| b.is_ascii_hexdigit());
+        if hashed && !keep.contains(stem) {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_only_from_v2_casts() {
+        let mut item = json!({});
+        add_cast_info(
+            &mut item,
+            &cast::parse_info("{\"version\": 1, \"stdout\": []}\n"),
+        );
+        assert!(item.get("duration").is_none());
+        add_cast_info(&mut item, &cast::parse_info("{\"version\": 2}\n"));
+        assert_eq!(item["duration"], json!(0.0));
+    }
+
+    #[test]
+    fn casts_resolve_next_to_the_timeline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let project = Project {
+            player: root.join("player"),
+            timelines: root.join("talk"),
+            casts: root.join("casts"),
+        };
+        fs::create_dir_all(project.timelines.join("rec")).unwrap();
+        fs::create_dir_all(&project.casts).unwrap();
+        fs::write(project.timelines.join("rec/a.cast"), "{\"version\":2}\n").unwrap();
+        fs::write(
+            project.casts.join("b.cast"),
+            "{\"version\":2}\n[1,\"o\",\"x\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            project.timelines.join("t.timeline"),
+            "timeline: t\ncast rec/a.cast 80x24\ncast /casts/b.cast 80x24\n",
+        )
+        .unwrap();
+        fs::write(
+            project.timelines.join("bad.timeline"),
+            "timeline: bad\ncast nope.cast 80x24\n",
+        )
+        .unwrap();
+        fs::write(
+            project.timelines.join("escape.timeline"),
+            "timeline: ../../escape\n",
+        )
+        .unwrap();
+
+        let built = build(&project, None).unwrap();
+        assert_eq!(built.names, ["t"]);
+        assert_eq!(built.casts, 2);
+        assert_eq!(built.problems.len(), 2);
+        assert!(
+            built
+                .problems
+                .iter()
+                .any(|p| p.ends_with("cast nope.cast not found"))
+        );
+        assert!(
+            built
+                .problems
+                .iter()
+                .any(|p| p.contains("invalid timeline name \"../../escape\""))
+        );
+        assert!(!root.join("escape.json").exists());
+        let json = fs::read(project.compiled().join("t.json")).unwrap();
+        let json: Value = serde_json::from_slice(&json).unwrap();
+        let path = json["items"][0]["castPath"].as_str().unwrap();
+        assert_eq!(
+            path,
+            format!("casts/{}.cast", fsx::short_hash(b"{\"version\":2}\n"))
+        );
+        assert!(project.public().join(path).is_file());
+        assert_eq!(json["items"][1]["duration"], 1.0);
+        assert_eq!(json["items"][1]["markers"], json!([]));
+    }
+
+    #[test]
+    fn partial_build_keeps_the_other_timelines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let project = Project {
+            player: root.join("player"),
+            timelines: root.join("talk"),
+            casts: root.join("casts"),
+        };
+        fs::create_dir_all(&project.timelines).unwrap();
+        let (a, b) = (
+            project.timelines.join("a.timeline"),
+            project.timelines.join("b.timeline"),
+        );
+        fs::write(&a, "timeline: a\n").unwrap();
+        fs::write(&b, "timeline: b\n").unwrap();
+        fs::write(project.timelines.join("new.timeline"), "timeline: new\n").unwrap();
+        fs::create_dir_all(project.compiled()).unwrap();
+        for name in ["a", "b"] {
+            fs::write(project.compiled().join(format!("{name}.json")), "old").unwrap();
+        }
+
+        let built = build(&project, Some(&BTreeSet::from([a]))).unwrap();
+        assert_eq!(built.names, ["a"]);
+        assert!(built.problems.is_empty());
+        let names = timeline::compiled_names(&project.compiled()).unwrap();
+        assert_eq!(names, ["a", "b"]);
+        let b_json = fs::read_to_string(project.compiled().join("b.json")).unwrap();
+        assert_eq!(b_json, "old");
+    }
+}
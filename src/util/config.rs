@@ -0,0 +1,230 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// `~/.config/shell-scene/config.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("shell-scene").join("config.toml"))
+}
+
+/// Settings read from the config file. Command line options take precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub ttyd: ClientOptions,
+}
+
+/// Load `explicit`, or the default config file if it exists.
+pub fn load(explicit: Option<&Path>) -> io::Result<Config> {
+    let path = match explicit {
+        Some(p) => p.to_path_buf(),
+        None => match default_path() {
+            Some(p) if p.exists() => p,
+            _ => return Ok(Config::default()),
+        },
+    };
+    let txt = std::fs::read_to_string(&path)?;
+    toml::from_str(&txt).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {e}", path.display()),
+        )
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorStyle {
+    Block,
+    Underline,
+    Bar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Renderer {
+    Dom,
+    Canvas,
+    Webgl,
+}
+
+/// A named theme, or xterm.js theme colors (`background`, `brightRed`, ...).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Theme {
+    Named(String),
+    Colors(BTreeMap<String, String>),
+}
+
+impl Theme {
+    /// Built-in themes. `asciinema` matches the player's default theme.
+    pub const NAMES: [&'static str; 2] = ["asciinema", "xterm"];
+
+    /// xterm.js colors, or `None` for the xterm.js defaults.
+    fn colors(&self) -> io::Result<Option<BTreeMap<String, String>>> {
+        match self {
+            Theme::Colors(c) => Ok(Some(c.clone())),
+            Theme::Named(n) => match n.as_str() {
+                "asciinema" => Ok(Some(asciinema_colors())),
+                "xterm" => Ok(None),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "unknown theme {n:?} (expected {} or a JSON file)",
+                        Self::NAMES.join(", ")
+                    ),
+                )),
+            },
+        }
+    }
+}
+
+fn asciinema_colors() -> BTreeMap<String, String> {
+    [
+        ("foreground", "#cccccc"),
+        ("background", "#121314"),
+        ("cursor", "#cccccc"),
+        ("black", "#000000"),
+        ("red", "#dd3c69"),
+        ("green", "#4ebf22"),
+        ("yellow", "#ddaf3c"),
+        ("blue", "#26b0d7"),
+        ("magenta", "#b954e1"),
+        ("cyan", "#54e1b9"),
+        ("white", "#d9d9d9"),
+        ("brightBlack", "#4c4c4c"),
+        ("brightRed", "#dd3c69"),
+        ("brightGreen", "#4ebf22"),
+        ("brightYellow", "#ddaf3c"),
+        ("brightBlue", "#26b0d7"),
+        ("brightMagenta", "#b954e1"),
+        ("brightCyan", "#54e1b9"),
+        ("brightWhite", "#ffffff"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+/// xterm.js client options passed to ttyd.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientOptions {
+    pub font_size: Option<u32>,
+    pub font_family: Option<String>,
+    pub line_height: Option<f64>,
+    pub cursor_style: Option<CursorStyle>,
+    pub cursor_blink: Option<bool>,
+    pub renderer: Option<Renderer>,
+    pub theme: Option<Theme>,
+}
+
+impl ClientOptions {
+    /// Options set in `self`, falling back to `other`.
+    pub fn or(self, other: ClientOptions) -> ClientOptions {
+        ClientOptions {
+            font_size: self.font_size.or(other.font_size),
+            font_family: self.font_family.or(other.font_family),
+            line_height: self.line_height.or(other.line_height),
+            cursor_style: self.cursor_style.or(other.cursor_style),
+            cursor_blink: self.cursor_blink.or(other.cursor_blink),
+            renderer: self.renderer.or(other.renderer),
+            theme: self.theme.or(other.theme),
+        }
+    }
+
+    /// `key=value` pairs for `ttyd -t`.
+    pub fn ttyd_options(&self) -> io::Result<Vec<String>> {
+        let mut opts = Vec::new();
+        if let Some(v) = self.font_size {
+            opts.push(format!("fontSize={v}"));
+        }
+        if let Some(v) = &self.font_family {
+            opts.push(format!("fontFamily={v}"));
+        }
+        if let Some(v) = self.line_height {
+            opts.push(format!("lineHeight={v}"));
+        }
+        if let Some(v) = self.cursor_style {
+            opts.push(format!("cursorStyle={}", value_name(v)));
+        }
+        if let Some(v) = self.cursor_blink {
+            opts.push(format!("cursorBlink={v}"));
+        }
+        if let Some(v) = self.renderer {
+            opts.push(format!("rendererType={}", value_name(v)));
+        }
+        if let Some(colors) = self
+            .theme
+            .as_ref()
+            .map(Theme::colors)
+            .transpose()?
+            .flatten()
+        {
+            let json = serde_json::to_string(&colors).map_err(io::Error::other)?;
+            opts.push(format!("theme={json}"));
+        }
+        Ok(opts)
+    }
+}
+
+fn value_name(v: impl ValueEnum) -> String {
+    v.to_possible_value()
+        .map(|p| p.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// `--theme NAME|FILE.json`: a built-in theme or a JSON object of xterm.js colors.
+pub fn parse_theme(arg: &str) -> io::Result<Theme> {
+    let path = Path::new(arg);
+    if !path.is_file() {
+        return Ok(Theme::Named(arg.to_string()));
+    }
+    let txt = std::fs::read_to_string(path)?;
+    serde_json::from_str(&txt)
+        .map(Theme::Colors)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{arg}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_to_ttyd_options() {
+        let cfg: Config = toml::from_str(
+            r##"
+            [ttyd]
+            font_family = "JetBrains Mono"
+            cursor_style = "bar"
+            renderer = "webgl"
+            theme = { background = "#000000" }
+            "##,
+        )
+        .unwrap();
+        let cli = ClientOptions {
+            font_size: Some(20),
+            cursor_style: Some(CursorStyle::Block),
+            ..Default::default()
+        };
+        assert_eq!(
+            cli.or(cfg.ttyd).ttyd_options().unwrap(),
+            [
+                "fontSize=20",
+                "fontFamily=JetBrains Mono",
+                "cursorStyle=block",
+                "rendererType=webgl",
+                r##"theme={"background":"#000000"}"##,
+            ]
+        );
+
+        let named = ClientOptions {
+            theme: Some(Theme::Named("asciinema".into())),
+            ..Default::default()
+        };
+        let opts = named.ttyd_options().unwrap();
+        assert!(opts[0].contains(r##""brightRed":"#dd3c69""##));
+    }
+}
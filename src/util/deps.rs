@@ -25,7 +25,4 @@ pub fn warn_optionals() {
     if !have("ss") && !have("netstat") {
         eprintln_warn("Neither 'ss' nor 'netstat' found; using bind-probe to find a free port.");
     }
-    if !have("xdg-open") {
-        eprintln_warn("'xdg-open' not found; not opening a browser automatically.");
-    }
 }
@@ -3,8 +3,11 @@ use chrono::Local;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
 
+/// Timestamp suffix used in default cast file names (`{session}-{timestamp}.cast`).
+pub const CAST_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
 pub fn now_yyyymmdd_hhmmss() -> String {
-    Local::now().format("%Y%m%d-%H%M%S").to_string()
+    Local::now().format(CAST_TIMESTAMP_FORMAT).to_string()
 }
 
 pub fn home_dir() -> PathBuf {
@@ -16,6 +19,41 @@ pub fn home_dir() -> PathBuf {
         })
 }
 
+/// Where `record` writes casts unless `--out` is given.
+pub fn default_cast_dir() -> PathBuf {
+    home_dir().join("casts")
+}
+
+/// Split a trailing `.takeN` off a cast file stem.
+pub fn split_take(stem: &str) -> (&str, Option<u32>) {
+    if let Some((base, n)) = stem.rsplit_once(".take")
+        && let Ok(n) = n.parse()
+    {
+        return (base, Some(n));
+    }
+    (stem, None)
+}
+
+/// `demo.cast` -> `demo.take2.cast`. Take 1 is the original file.
+pub fn take_path(base: &Path, take: u32) -> PathBuf {
+    let stem = base.file_stem().unwrap_or_default().to_string_lossy();
+    let (stem, _) = split_take(&stem);
+    let name = if take <= 1 {
+        format!("{stem}.cast")
+    } else {
+        format!("{stem}.take{take}.cast")
+    };
+    base.with_file_name(name)
+}
+
+/// First 16 hex digits of the SHA-1 of `data`; names content-addressed
+/// build outputs.
+pub fn short_hash(data: &[u8]) -> String {
+    let mut hex = sha1_smol::Sha1::from(data).digest().to_string();
+    hex.truncate(16);
+    hex
+}
+
 pub fn ensure_writable_dir(p: &Path) {
     if let Err(e) = fs::create_dir_all(p) {
         eprintln_err(&format!(
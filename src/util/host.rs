@@ -0,0 +1,164 @@
+use crate::util::deps;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Where the tmux server of a recording runs. asciinema and ttyd always run
+/// locally; only tmux (and the shell inside it) may live elsewhere.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Host {
+    #[default]
+    Local,
+    /// Reached through `ssh` (or a compatible command) as `ssh DEST -- CMD`.
+    Ssh { ssh: String, dest: String },
+}
+
+impl Host {
+    pub fn is_local(&self) -> bool {
+        *self == Host::Local
+    }
+
+    /// A command running `program args...` on this host.
+    pub fn command(&self, program: &str, args: &[&str]) -> Command {
+        match self {
+            Host::Local => {
+                let mut cmd = Command::new(program);
+                cmd.args(args);
+                cmd
+            }
+            Host::Ssh { ssh, dest } => {
+                let mut cmd = Command::new(ssh);
+                cmd.arg(dest).arg("--").arg(remote_cmdline(program, args));
+                cmd
+            }
+        }
+    }
+
+    /// Whether `program` is installed on this host.
+    pub fn has(&self, program: &str) -> bool {
+        match self {
+            Host::Local => deps::have(program),
+            Host::Ssh { .. } => self
+                .command("sh", &["-c", &format!("command -v {}", sh_quote(program))])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success()),
+        }
+    }
+
+    /// `program args...` as a single shell command line (for `asciinema rec -c`).
+    /// Remote commands get a tty (`ssh -t`) so they can be attached to.
+    pub fn shell_cmdline(&self, program: &str, args: &[&str]) -> String {
+        match self {
+            Host::Local => remote_cmdline(program, args),
+            Host::Ssh { ssh, dest } => format!(
+                "{} -t {} -- {}",
+                sh_quote(ssh),
+                sh_quote(dest),
+                sh_quote(&remote_cmdline(program, args))
+            ),
+        }
+    }
+}
+
+/// A disposable container for the recorded shell, run through a
+/// podman/docker compatible CLI. tmux itself stays outside the container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Container {
+    pub runtime: String,
+    pub image: String,
+}
+
+impl Container {
+    /// Where the working directory is mounted inside the container.
+    pub const WORKDIR: &'static str = "/work";
+
+    pub fn name(session: &str) -> String {
+        format!("shell-scene-{session}")
+    }
+
+    /// Command for the tmux pane: an interactive login shell in a fresh
+    /// container, with `workdir` bind-mounted at [`Container::WORKDIR`].
+    pub fn shell_command(&self, session: &str, workdir: Option<&Path>) -> Vec<String> {
+        let mut cmd: Vec<String> = [
+            self.runtime.as_str(),
+            "run",
+            "--rm",
+            "-it",
+            "--name",
+            &Self::name(session),
+            "--hostname",
+            session,
+        ]
+        .map(String::from)
+        .into();
+        if let Some(dir) = workdir {
+            cmd.push("-v".into());
+            cmd.push(format!("{}:{}", dir.display(), Self::WORKDIR));
+            cmd.push("-w".into());
+            cmd.push(Self::WORKDIR.into());
+        }
+        cmd.extend([self.image.clone(), "bash".into(), "-l".into()]);
+        cmd
+    }
+}
+
+fn remote_cmdline(program: &str, args: &[&str]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().copied())
+        .map(sh_quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quote `s` for a POSIX shell.
+pub fn sh_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-_./:=@,+%".contains(&b))
+    {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(sh_quote("ttyd-demo"), "ttyd-demo");
+        assert_eq!(sh_quote("it's here"), r"'it'\''s here'");
+        assert_eq!(sh_quote(""), "''");
+    }
+
+    #[test]
+    fn container_shell() {
+        let c = Container {
+            runtime: "podman".into(),
+            image: "debian:stable".into(),
+        };
+        assert_eq!(
+            c.shell_command("demo", Some(Path::new("/home/me/proj")))
+                .join(" "),
+            "podman run --rm -it --name shell-scene-demo --hostname demo \
+             -v /home/me/proj:/work -w /work debian:stable bash -l"
+        );
+    }
+
+    #[test]
+    fn ssh_command_line() {
+        let host = Host::Ssh {
+            ssh: "ssh".into(),
+            dest: "me@lab".into(),
+        };
+        assert_eq!(
+            host.shell_cmdline("tmux", &["-L", "ttyd-demo", "attach", "-t", "demo"]),
+            "ssh -t me@lab -- 'tmux -L ttyd-demo attach -t demo'"
+        );
+        let cmd = host.command("tmux", &["new-session", "-c", "/srv/my app"]);
+        let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(args, ["me@lab", "--", "tmux new-session -c '/srv/my app'"]);
+    }
+}
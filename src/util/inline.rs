@@ -0,0 +1,138 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+/// `bytes` as a `data:` URL.
+pub fn data_url(mime: &str, bytes: &[u8]) -> String {
+    let mime = mime.replace(' ', "");
+    format!("data:{mime};base64,{}", BASE64.encode(bytes))
+}
+
+/// Text that can go inside a `<script>` or `<style>` element.
+fn element_safe(text: &str) -> String {
+    text.replace("</script", "<\\/script")
+        .replace("</style", "<\\/style")
+}
+
+/// `html` with its `<script src>` and stylesheet `<link>`s replaced by
+/// inline elements and other `<link href>`s (icons) by data URLs. `file`
+/// returns the content and type of a referenced file; references it doesn't
+/// know are left alone.
+pub fn inline_html(html: &str, file: impl Fn(&str) -> Option<(Vec<u8>, &'static str)>) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..=end];
+        let lower = tag.to_ascii_lowercase();
+        let replaced = if lower.starts_with("<script") {
+            attr(tag, "src").and_then(|src| {
+                let (js, _) = file(src)?;
+                let close = rest[end + 1..].find("</script>")?;
+                let module = lower.contains("type=\"module\"");
+                rest = &rest[end + 1 + close..];
+                let js = element_safe(&String::from_utf8_lossy(&js));
+                Some(match module {
+                    true => format!("<script type=\"module\">{js}"),
+                    false => format!("<script>{js}"),
+                })
+            })
+        } else if lower.starts_with("<link") {
+            attr(tag, "href").and_then(|href| {
+                let (bytes, mime) = file(href)?;
+                rest = &rest[end + 1..];
+                if lower.contains("rel=\"stylesheet\"") {
+                    let css = inline_css(&String::from_utf8_lossy(&bytes), href, &file);
+                    Some(format!("<style>{}</style>", element_safe(&css)))
+                } else {
+                    Some(tag.replace(href, &data_url(mime, &bytes)))
+                }
+            })
+        } else {
+            None
+        };
+        match replaced {
+            Some(s) => out.push_str(&s),
+            None => {
+                out.push_str(tag);
+                rest = &rest[end + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `css` (from `css_path`) with `url(...)`s of known files made data URLs.
+fn inline_css(
+    css: &str,
+    css_path: &str,
+    file: &impl Fn(&str) -> Option<(Vec<u8>, &'static str)>,
+) -> String {
+    let dir = css_path.rsplit_once('/').map_or("", |(d, _)| d);
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("url(") {
+        out.push_str(&rest[..start + 4]);
+        rest = &rest[start + 4..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let url = rest[..end].trim().trim_matches(['"', '\'']);
+        let path = match url {
+            u if u.starts_with("data:") || u.contains("://") => None,
+            u if u.starts_with('/') => Some(u.to_string()),
+            u => Some(format!("{dir}/{}", u.trim_start_matches("./"))),
+        };
+        match path.and_then(|p| file(&p)) {
+            Some((bytes, mime)) => out.push_str(&format!("\"{}\"", data_url(mime, &bytes))),
+            None => out.push_str(&rest[..end]),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The value of attribute `name` in `tag` (double-quoted values only, as
+/// vite writes them).
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(" {name}=\"");
+    let start = tag.find(&needle)? + needle.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inlines_scripts_styles_and_icons() {
+        let html = concat!(
+            r#"<link rel="icon" href="./terminal.svg" />"#,
+            r#"<script type="module" crossorigin src="./assets/app.js"></script>"#,
+            r#"<link rel="stylesheet" crossorigin href="./assets/app.css">"#,
+            r#"<script src="https://example.com/x.js"></script>"#,
+        );
+        let out = inline_html(html, |path| match path {
+            "./terminal.svg" => Some((b"<svg/>".to_vec(), "image/svg+xml")),
+            "./assets/app.js" => Some((b"let s = '</script>';".to_vec(), "text/javascript")),
+            "./assets/app.css" => Some((b"a{background:url(bg.png)}".to_vec(), "text/css")),
+            "./assets/bg.png" => Some((b"png".to_vec(), "image/png")),
+            _ => None,
+        });
+        assert_eq!(
+            out,
+            concat!(
+                r#"<link rel="icon" href="data:image/svg+xml;base64,PHN2Zy8+" />"#,
+                r#"<script type="module">let s = '<\/script>';</script>"#,
+                r#"<style>a{background:url("data:image/png;base64,cG5n")}</style>"#,
+                r#"<script src="https://example.com/x.js"></script>"#,
+            )
+        );
+    }
+}
@@ -1,7 +1,23 @@
+pub mod browser;
+pub mod cast;
+pub mod compile;
+pub mod config;
 pub mod deps;
 pub mod fsx;
+pub mod host;
+pub mod http;
+pub mod human;
+pub mod inline;
 pub mod net;
+pub mod pause;
+pub mod player;
 pub mod proc;
+pub mod pronunciation;
+pub mod recorder;
+pub mod scenescript;
+pub mod speech;
+pub mod timeline;
+pub mod watch;
 
 // tiny stderr helpers (kept local)
 pub fn eprintln_err(msg: &str) {
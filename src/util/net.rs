@@ -1,13 +1,14 @@
 use crate::util::eprintln_err;
-use std::net::{TcpListener, TcpStream};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpListener, TcpStream};
 use std::thread;
 use std::time::Duration;
 
-pub fn find_free_port(start: u16) -> u16 {
+/// First port from `start` that can be bound on `bind`.
+pub fn find_free_port(bind: IpAddr, start: u16) -> u16 {
     let first = if start == 0 { 1 } else { start };
     let mut p = first;
     loop {
-        match TcpListener::bind(("127.0.0.1", p)) {
+        match TcpListener::bind((bind, p)) {
             Ok(listener) => {
                 drop(listener);
                 return p;
@@ -18,7 +19,9 @@ pub fn find_free_port(start: u16) -> u16 {
                     p = 1;
                 }
                 if p == first {
-                    eprintln_err("No free TCP port found in range 1..=65535.");
+                    eprintln_err(&format!(
+                        "No free TCP port found on {bind} in range 1..=65535."
+                    ));
                     std::process::exit(1);
                 }
             }
@@ -26,11 +29,44 @@ pub fn find_free_port(start: u16) -> u16 {
     }
 }
 
-pub fn wait_for_tcp(host: &str, port: u16, attempts: usize, sleep_ms: u64) {
+/// Address to connect to for a server listening on `bind`: a wildcard bind
+/// is reached through loopback.
+pub fn local_addr(bind: IpAddr) -> IpAddr {
+    match bind {
+        IpAddr::V4(a) if a.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(a) if a.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        a => a,
+    }
+}
+
+/// `scheme://addr:port/`, bracketing IPv6 addresses.
+pub fn url(scheme: &str, addr: IpAddr, port: u16) -> String {
+    match addr {
+        IpAddr::V4(a) => format!("{scheme}://{a}:{port}/"),
+        IpAddr::V6(a) => format!("{scheme}://[{a}]:{port}/"),
+    }
+}
+
+pub fn wait_for_tcp(addr: IpAddr, port: u16, attempts: usize, sleep_ms: u64) {
     for _ in 0..attempts {
-        if TcpStream::connect((host, port)).is_ok() {
+        if TcpStream::connect((addr, port)).is_ok() {
             return;
         }
         thread::sleep(Duration::from_millis(sleep_ms));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_bind_urls() {
+        let any: IpAddr = "0.0.0.0".parse().unwrap();
+        assert_eq!(url("http", local_addr(any), 7681), "http://127.0.0.1:7681/");
+        let any6: IpAddr = "::".parse().unwrap();
+        assert_eq!(url("https", local_addr(any6), 8443), "https://[::1]:8443/");
+        let lan: IpAddr = "192.168.1.20".parse().unwrap();
+        assert_eq!(local_addr(lan), lan);
+    }
+}
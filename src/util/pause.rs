@@ -0,0 +1,130 @@
+use crate::util::eprintln_warn;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// tmux option (on the `ttyd-{session}` server) naming the pause log of the
+/// recording in progress.
+pub const TMUX_OPTION: &str = "@shell-scene-pauses";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mark {
+    Start,
+    Pause,
+    Resume,
+}
+
+impl Mark {
+    fn as_str(self) -> &'static str {
+        match self {
+            Mark::Start => "start",
+            Mark::Pause => "pause",
+            Mark::Resume => "resume",
+        }
+    }
+}
+
+/// Pause log kept next to the cast while recording.
+pub fn log_path(ascii_out: &Path) -> PathBuf {
+    let mut p = ascii_out.as_os_str().to_owned();
+    p.push(".pauses");
+    PathBuf::from(p)
+}
+
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Append a mark stamped with the current wall-clock time.
+pub fn append(log: &Path, mark: Mark) -> io::Result<()> {
+    let mut f = OpenOptions::new().create(true).append(true).open(log)?;
+    writeln!(f, "{} {}", mark.as_str(), now_ms())
+}
+
+/// Start a fresh log (the recording is about to begin).
+pub fn start(log: &Path) -> io::Result<()> {
+    fs::write(log, "")?;
+    append(log, Mark::Start)
+}
+
+/// The marks of a log; lines that aren't one are skipped with a warning.
+fn parse(txt: &str) -> Vec<(Mark, u128)> {
+    txt.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .filter_map(|(i, l)| {
+            let mark = parse_line(l);
+            if mark.is_none() {
+                eprintln_warn(&format!("Ignoring pause log line {}: {l:?}", i + 1));
+            }
+            mark
+        })
+        .collect()
+}
+
+fn parse_line(line: &str) -> Option<(Mark, u128)> {
+    let (kind, ms) = line.split_once(' ')?;
+    let mark = match kind {
+        "start" => Mark::Start,
+        "pause" => Mark::Pause,
+        "resume" => Mark::Resume,
+        _ => return None,
+    };
+    Some((mark, ms.trim().parse().ok()?))
+}
+
+/// True if the last mark in the log is a pause.
+pub fn is_paused(log: &Path) -> bool {
+    let txt = fs::read_to_string(log).unwrap_or_default();
+    parse(&txt).last().is_some_and(|(m, _)| *m == Mark::Pause)
+}
+
+/// Paused intervals in seconds relative to the start mark. A pause that was
+/// never resumed runs to the end of the recording.
+pub fn intervals(txt: &str) -> Vec<(f64, f64)> {
+    let marks = parse(txt);
+    let Some(start) = marks
+        .iter()
+        .find(|(m, _)| *m == Mark::Start)
+        .map(|(_, ms)| *ms)
+    else {
+        return Vec::new();
+    };
+    let secs = |ms: u128| ms.saturating_sub(start) as f64 / 1000.0;
+    let mut out = Vec::new();
+    let mut paused_at = None;
+    for (mark, ms) in marks {
+        match mark {
+            Mark::Pause if paused_at.is_none() => paused_at = Some(secs(ms)),
+            Mark::Resume => {
+                if let Some(from) = paused_at.take() {
+                    out.push((from, secs(ms)));
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = paused_at {
+        out.push((from, f64::INFINITY));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_from_log() {
+        let log = "start 1000\npause 3000\npause 3500\nresume 5000\npause 9000\n";
+        assert_eq!(intervals(log), vec![(2.0, 4.0), (8.0, f64::INFINITY)]);
+        let log = "start 1000\npause 3s\nresume 5000\npause\nbogus 1\n";
+        assert_eq!(intervals(log), vec![]);
+        assert_eq!(parse_line("resume 5000"), Some((Mark::Resume, 5000)));
+        assert_eq!(parse_line("pause soon"), None);
+    }
+}
@@ -0,0 +1,74 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// `PLAYER`: the built player as (path, contents), generated by build.rs.
+include!(concat!(env!("OUT_DIR"), "/player.rs"));
+
+/// A file of the built player, by its path relative to the site root.
+pub type PlayerFile = (String, Cow<'static, [u8]>);
+
+/// The built player's files: read from `dist` if given, otherwise the copy
+/// embedded at build time (empty if the player wasn't built then).
+pub fn files(dist: Option<&Path>) -> io::Result<Vec<PlayerFile>> {
+    let Some(dist) = dist else {
+        return Ok(PLAYER
+            .iter()
+            .map(|(p, b)| (p.to_string(), Cow::Borrowed(*b)))
+            .collect());
+    };
+    if !dist.join("index.html").is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no index.html in {}", dist.display()),
+        ));
+    }
+    let mut out = Vec::new();
+    read_dir(dist, "", &mut out)?;
+    out.sort();
+    Ok(out)
+}
+
+fn read_dir(dir: &Path, prefix: &str, out: &mut Vec<PlayerFile>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let rel = format!("{prefix}{name}");
+        if entry.file_type()?.is_dir() {
+            read_dir(&entry.path(), &format!("{rel}/"), out)?;
+        } else {
+            out.push((rel, Cow::Owned(fs::read(entry.path())?)));
+        }
+    }
+    Ok(())
+}
+
+/// `index.html` with a `<base href>`, so the site works from any URL below
+/// `base` (e.g. `/talks/` or `https://example.com/talks/`).
+pub fn with_base(html: &str, base: &str) -> String {
+    let base = if base.ends_with('/') {
+        base.to_string()
+    } else {
+        format!("{base}/")
+    };
+    let tag = format!("<base href=\"{}\" />", base.replace('"', "&quot;"));
+    match html.find("<head>") {
+        Some(i) => format!("{}\n    {tag}{}", &html[..i + 6], &html[i + 6..]),
+        None => format!("{tag}\n{html}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_tag_goes_first_in_head() {
+        let html = "<html><head><title>x</title></head></html>";
+        assert_eq!(
+            with_base(html, "/talks"),
+            "<html><head>\n    <base href=\"/talks/\" /><title>x</title></head></html>"
+        );
+    }
+}
@@ -1,38 +1,101 @@
 use std::io;
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::util::eprintln_err;
-use crate::util::net::wait_for_tcp;
+use crate::util::browser;
+use crate::util::cast;
+use crate::util::host::{Container, Host, sh_quote};
+use crate::util::human::{human_duration, human_size};
+use crate::util::net;
+use crate::util::pause;
+use crate::util::recorder::{self, Recorder};
+use crate::util::{eprintln_err, eprintln_warn};
 
 pub fn run_tmux(args: &[&str]) -> io::Result<()> {
-    let status = Command::new("tmux").args(args).status()?;
+    run_tmux_on(&Host::Local, args)
+}
+
+/// Run tmux on `host`.
+pub fn run_tmux_on(host: &Host, args: &[&str]) -> io::Result<()> {
+    let status = host.command("tmux", args).status()?;
     if !status.success() {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("tmux {:?} failed: {}", args, status),
-        ))
+        Err(io::Error::other(format!(
+            "tmux {:?} failed: {}",
+            args, status
+        )))
     } else {
         Ok(())
     }
 }
 
-pub fn record_flow(
+/// Run tmux and return its trimmed stdout.
+pub fn tmux_output(args: &[&str]) -> io::Result<String> {
+    tmux_output_on(&Host::Local, args)
+}
+
+/// Run tmux on `host` and return its trimmed stdout.
+pub fn tmux_output_on(host: &Host, args: &[&str]) -> io::Result<String> {
+    let out = host.command("tmux", args).stderr(Stdio::null()).output()?;
+    if !out.status.success() {
+        return Err(io::Error::other(format!(
+            "tmux {:?} failed: {}",
+            args, out.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Directory holding this user's tmux sockets (`$TMUX_TMPDIR/tmux-UID`).
+pub fn tmux_socket_dir() -> PathBuf {
+    let base = std::env::var_os("TMUX_TMPDIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/tmp"));
+    #[cfg(unix)]
+    let uid = unsafe { libc::getuid() };
+    #[cfg(not(unix))]
+    let uid = 0;
+    base.join(format!("tmux-{uid}"))
+}
+
+/// Names of the `ttyd-{session}` sockets used for recordings (sorted).
+pub fn ttyd_sockets() -> Vec<String> {
+    ttyd_sockets_in(&tmux_socket_dir())
+}
+
+fn ttyd_sockets_in(dir: &Path) -> Vec<String> {
+    let mut socks: Vec<String> = std::fs::read_dir(dir)
+        .map(|rd| {
+            rd.flatten()
+                .filter_map(|e| e.file_name().into_string().ok())
+                .filter(|n| n.starts_with("ttyd-"))
+                .collect()
+        })
+        .unwrap_or_default();
+    socks.sort();
+    socks
+}
+
+/// Create the tmux session on `sock` unless it already exists, and size its
+/// window to exactly `cols`x`rows`. Without a `working_dir` tmux starts in
+/// the server's current directory (the login directory on a remote host).
+/// `shell` is the pane command (default: `bash -l`). True if the session
+/// was created, false if it already existed.
+pub fn prepare_session(
+    host: &Host,
+    sock: &str,
     session: &str,
     cols: u32,
     rows: u32,
-    ascii_out: &Path,
-    working_dir: &Path,
-    kill_on_detach: bool,
-) -> i32 {
-    use std::fs;
-    if let Some(p) = ascii_out.parent() {
-        let _ = fs::create_dir_all(p);
-    }
-
-    let sock = format!("ttyd-{session}");
-    let has_session = Command::new("tmux")
-        .args(["-L", &sock, "has-session", "-t", session])
+    working_dir: Option<&Path>,
+    shell: Option<&[String]>,
+) -> io::Result<bool> {
+    let has_session = host
+        .command("tmux", &["-L", sock, "has-session", "-t", session])
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .status()
@@ -40,101 +103,321 @@ pub fn record_flow(
         .unwrap_or(false);
 
     if !has_session {
-        let args = [
+        let working_dir = working_dir.map(|p| p.to_string_lossy().to_string());
+        let (cols, rows) = (cols.to_string(), rows.to_string());
+        let mut args = vec!["-L", sock, "new-session"];
+        if let Some(dir) = &working_dir {
+            args.extend(["-c", dir]);
+        }
+        args.extend(["-d", "-s", session, "-x", &cols, "-y", &rows]);
+        match shell {
+            Some(shell) => args.extend(shell.iter().map(String::as_str)),
+            None => args.extend(["bash", "-l"]),
+        }
+        run_tmux_on(host, &args)?;
+        let _ = run_tmux_on(host, &["-L", sock, "set", "-g", "status", "off"]);
+    }
+
+    let _ = run_tmux_on(host, &["-L", sock, "set", "-g", "window-size", "manual"]);
+    let _ = run_tmux_on(host, &["-L", sock, "set", "-g", "status", "off"]);
+    let _ = run_tmux_on(
+        host,
+        &[
             "-L",
-            &sock,
-            "new-session",
-            "-c",
-            &working_dir.to_string_lossy(),
-            "-d",
-            "-s",
-            session,
+            sock,
+            "resize-window",
+            "-t",
+            &format!("{session}:0"),
             "-x",
             &cols.to_string(),
             "-y",
             &rows.to_string(),
-            "bash",
-            "-l",
-        ];
-        if let Err(e) = run_tmux(&args) {
+        ],
+    );
+    Ok(!has_session)
+}
+
+/// The path a container mounts for the working directory `dir`. The runtime
+/// runs in the pane, which already is in `dir`, so a relative path would
+/// resolve twice (or name a volume); a remote one is left to the remote
+/// shell.
+fn mount_dir(host: &Host, dir: &Path) -> PathBuf {
+    match host {
+        Host::Local => std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf()),
+        Host::Ssh { .. } => dir.to_path_buf(),
+    }
+}
+
+/// Clear the screen and scrollback of a staged session so the recording
+/// starts from a fresh prompt.
+pub fn clear_session(host: &Host, sock: &str, session: &str) {
+    let _ = run_tmux_on(host, &["-L", sock, "send-keys", "-t", session, "C-l"]);
+    let _ = run_tmux_on(host, &["-L", sock, "clear-history", "-t", session]);
+    // give the shell a moment to redraw its prompt before attaching
+    thread::sleep(Duration::from_millis(200));
+}
+
+/// What `record_flow` records and how.
+#[derive(Debug, Clone)]
+pub struct RecordSpec {
+    pub session: String,
+    pub cols: u32,
+    pub rows: u32,
+    pub ascii_out: PathBuf,
+    pub working_dir: Option<PathBuf>,
+    /// Where tmux runs.
+    pub host: Host,
+    /// Run the shell in a disposable container instead of directly.
+    pub container: Option<Container>,
+    pub kill_on_detach: bool,
+    /// Append to an existing cast instead of overwriting it.
+    pub append: bool,
+    /// Clear the screen of an existing session before recording.
+    pub clear: bool,
+    pub recorder: Recorder,
+}
+
+pub fn record_flow(spec: &RecordSpec) -> i32 {
+    use std::fs;
+    let RecordSpec {
+        session,
+        cols,
+        rows,
+        ascii_out,
+        working_dir,
+        host,
+        container,
+        kill_on_detach,
+        append,
+        clear,
+        recorder,
+    } = spec;
+    let (cols, rows) = (*cols, *rows);
+    if let Some(p) = ascii_out.parent() {
+        let _ = fs::create_dir_all(p);
+    }
+
+    let sock = format!("ttyd-{session}");
+    let mount = working_dir.as_deref().map(|d| mount_dir(host, d));
+    let shell = container
+        .as_ref()
+        .map(|c| c.shell_command(session, mount.as_deref()));
+    match prepare_session(
+        host,
+        &sock,
+        session,
+        cols,
+        rows,
+        working_dir.as_deref(),
+        shell.as_deref(),
+    ) {
+        Ok(false) if container.is_some() => eprintln_warn(&format!(
+            "Session {session:?} already exists, so --container is ignored (kill it with `shell-scene sessions kill --session {session}` to start it in a container)"
+        )),
+        Ok(_) => {}
+        Err(e) => {
             eprintln_err(&format!("Failed to create tmux session: {e}"));
             return 2;
         }
-        let _ = run_tmux(&["-L", &sock, "set", "-g", "status", "off"]);
+    }
+    if *clear {
+        clear_session(host, &sock, session);
     }
 
-    let _ = run_tmux(&["-L", &sock, "set", "-g", "window-size", "manual"]);
-    let _ = run_tmux(&["-L", &sock, "set", "-g", "status", "off"]);
-    let _ = run_tmux(&[
-        "-L",
-        &sock,
-        "resize-window",
-        "-t",
-        &format!("{session}:0"),
-        "-x",
-        &cols.to_string(),
-        "-y",
-        &rows.to_string(),
-    ]);
+    let pause_log = pause::log_path(ascii_out);
+    if pause_log.exists() {
+        eprintln_warn(&format!(
+            "Discarding the pause log of an earlier recording: {}",
+            pause_log.display()
+        ));
+    }
+    if host.is_local() {
+        // the key bindings run this binary, which only exists locally
+        install_pause_keys(&sock, session, &pause_log);
+    }
+
+    // appended events continue from the last one in the cast
+    let append = *append && ascii_out.exists();
+    let offset = if append {
+        cast::read_info(ascii_out)
+            .map(|i| i.duration)
+            .unwrap_or(0.0)
+    } else {
+        0.0
+    };
 
     eprintln!(
-        "[ttyd] Recording to: {} (size {}x{})",
+        "[ttyd] {} to: {} (size {}x{})",
+        if append { "Appending" } else { "Recording" },
         ascii_out.display(),
         cols,
         rows
     );
 
-    let attach_cmd = format!("tmux -L \"{}\" attach -t \"{}\"", sock, session);
-    let mut rec = Command::new("asciinema");
-    rec.arg("rec")
-        .arg("--overwrite")
-        .arg("-q")
-        .arg("--cols")
-        .arg(cols.to_string())
-        .arg("--rows")
-        .arg(rows.to_string())
-        .arg(ascii_out.to_string_lossy().to_string())
-        .arg("-c")
-        .arg(&attach_cmd)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
-
-    let status = rec.spawn().and_then(|mut child| child.wait());
+    let attach_cmd = host.shell_cmdline("tmux", &["-L", &sock, "attach", "-t", session]);
+    if let Err(e) = pause::start(&pause_log) {
+        eprintln_err(&format!("Failed to write {}: {e}", pause_log.display()));
+        // an old log must not cut pauses out of this recording
+        let _ = fs::remove_file(&pause_log);
+    }
+    let status = match recorder {
+        Recorder::Native => recorder::record(ascii_out, cols, rows, append, offset, &attach_cmd),
+        Recorder::Asciinema => Command::new("asciinema")
+            .arg("rec")
+            .arg(if append { "--append" } else { "--overwrite" })
+            .arg("-q")
+            .arg("--cols")
+            .arg(cols.to_string())
+            .arg("--rows")
+            .arg(rows.to_string())
+            .arg(ascii_out.to_string_lossy().to_string())
+            .arg("-c")
+            .arg(&attach_cmd)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .map(|s| s.code().unwrap_or(1)),
+    };
     let rc = match status {
-        Ok(s) => s.code().unwrap_or(1),
+        Ok(code) => code,
         Err(e) => {
-            eprintln_err(&format!("Failed to run asciinema: {e}"));
+            eprintln_err(&format!("Recording with {} failed: {e}", recorder.as_str()));
             1
         }
     };
 
-    if kill_on_detach {
-        let _ = run_tmux(&["-L", &sock, "kill-session", "-t", session]);
-        let _ = run_tmux(&["-L", &sock, "kill-server"]);
+    if host.is_local() {
+        let _ = run_tmux(&["-L", &sock, "set", "-gu", pause::TMUX_OPTION]);
+    }
+    if let Err(e) = stitch_pauses(ascii_out, &pause_log, offset) {
+        eprintln_err(&format!("Failed to remove pauses from the cast: {e}"));
+    }
+
+    if *kill_on_detach {
+        let _ = run_tmux_on(host, &["-L", &sock, "kill-session", "-t", session]);
+        let _ = run_tmux_on(host, &["-L", &sock, "kill-server"]);
+        if let Some(c) = container {
+            let _ = host
+                .command(&c.runtime, &["rm", "-f", &Container::name(session)])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
     }
 
     rc
 }
 
+/// Bind `prefix P` / `prefix R` to pause and resume this recording, and
+/// point the control commands at its pause log.
+fn install_pause_keys(sock: &str, session: &str, pause_log: &Path) {
+    let _ = run_tmux(&[
+        "-L",
+        sock,
+        "set",
+        "-g",
+        pause::TMUX_OPTION,
+        &pause_log.to_string_lossy(),
+    ]);
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    for (key, action) in [("P", "pause"), ("R", "resume")] {
+        let cmd = format!(
+            "{} record {action} --session {}",
+            sh_quote(&exe.to_string_lossy()),
+            sh_quote(session)
+        );
+        let _ = run_tmux(&["-L", sock, "bind-key", key, "run-shell", "-b", &cmd]);
+    }
+}
+
+/// Cut paused stretches out of the finished cast and drop the pause log.
+/// `offset` is where this recording starts in the cast (non-zero when appending).
+fn stitch_pauses(ascii_out: &Path, pause_log: &Path, offset: f64) -> io::Result<()> {
+    let log = match std::fs::read_to_string(pause_log) {
+        Ok(s) => s,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let intervals: Vec<(f64, f64)> = pause::intervals(&log)
+        .into_iter()
+        .map(|(a, b)| (a + offset, b + offset))
+        .collect();
+    if !intervals.is_empty() {
+        let txt = std::fs::read_to_string(ascii_out)?;
+        std::fs::write(ascii_out, cast::cut_intervals(&txt, &intervals))?;
+        eprintln!("[ttyd] Removed {} pause(s) from the cast", intervals.len());
+    }
+    std::fs::remove_file(pause_log)
+}
+
+/// How ttyd serves the recording terminal.
+#[derive(Debug, Clone)]
+pub struct TtydOptions {
+    /// `ttyd`, or the built-in `shell-scene web-terminal` which takes the same options.
+    pub program: Vec<String>,
+    pub port: u16,
+    /// xterm.js client options as `key=value` (see `ClientOptions::ttyd_options`).
+    pub client: Vec<String>,
+    /// Address ttyd listens on; loopback unless recording from another machine.
+    pub bind: IpAddr,
+    /// `USER:PASSWORD` for HTTP basic auth.
+    pub credential: Option<String>,
+    /// PEM certificate and key; serves HTTPS when set.
+    pub tls: Option<(PathBuf, PathBuf)>,
+    pub open: browser::Open,
+}
+
+impl TtydOptions {
+    fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-p".to_string(),
+            self.port.to_string(),
+            "-i".to_string(),
+            self.bind.to_string(),
+        ];
+        for opt in &self.client {
+            args.extend(["-t".to_string(), opt.clone()]);
+        }
+        if let Some(cred) = &self.credential {
+            args.extend(["-c".to_string(), cred.clone()]);
+        }
+        if let Some((cert, key)) = &self.tls {
+            args.extend([
+                "-S".to_string(),
+                "-C".to_string(),
+                cert.to_string_lossy().to_string(),
+                "-K".to_string(),
+                key.to_string_lossy().to_string(),
+            ]);
+        }
+        args
+    }
+
+    fn scheme(&self) -> &'static str {
+        if self.tls.is_some() { "https" } else { "http" }
+    }
+}
+
 pub fn spawn_ttyd_and_wait(
-    port: u16,
-    font_size: u32,
-    title: &str,
+    ttyd: &TtydOptions,
+    session: &str,
+    host: &Host,
+    ascii_out: &Path,
     envs: &[(&str, String)],
     cmd_and_args: &[String],
 ) -> i32 {
-    let mut cmd = Command::new("ttyd");
-    cmd.arg("-p")
-        .arg(port.to_string())
+    let mut cmd = Command::new(&ttyd.program[0]);
+    cmd.args(&ttyd.program[1..])
+        .args(ttyd.args())
         .arg("-o")
         .arg("-W")
         .arg("-t")
-        .arg(format!("fontSize={font_size}"))
-        .arg("-t")
         .arg("disableReconnect=true")
         .arg("-t")
-        .arg(format!("titleFixed={title}"))
+        .arg(format!("titleFixed={session}"))
         .arg("env");
     for (k, v) in envs {
         cmd.arg(format!("{k}={v}"));
@@ -145,51 +428,459 @@ pub fn spawn_ttyd_and_wait(
     cmd.stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .stdin(Stdio::null());
+    // in its own process group, so a Ctrl-C in the terminal reaches only us
+    // and not ttyd, the recorder and tmux before we can stop them in order
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    // and, as it no longer gets our terminal's signals, it must not outlive
+    // us with a writable shell if we die without stopping it
+    #[cfg(target_os = "linux")]
+    unsafe {
+        std::os::unix::process::CommandExt::pre_exec(&mut cmd, || {
+            if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
 
     let mut child = match cmd.spawn() {
         Ok(c) => c,
         Err(e) => {
-            eprintln_err(&format!("Failed to spawn ttyd: {e}"));
+            eprintln_err(&format!("Failed to spawn {}: {e}", ttyd.program[0]));
             return 1;
         }
     };
 
-    // ctrl-c -> SIGTERM child
-    let child_id = child.id();
-    ctrlc::set_handler(move || {
-        #[cfg(unix)]
-        {
-            let _ = unsafe { libc::kill(child_id as i32, libc::SIGTERM) };
-        }
-        #[cfg(not(unix))]
-        {
-            let _ = child_id;
+    let presses = Arc::new(AtomicUsize::new(0));
+    count_stop_signals(&presses);
+
+    let local = net::local_addr(ttyd.bind);
+    let url = net::url(ttyd.scheme(), local, ttyd.port);
+    eprintln!("[ttyd] Waiting for {url} ...");
+    net::wait_for_tcp(local, ttyd.port, 100, 50);
+
+    let mut window = browser::open(&ttyd.open, &url);
+
+    eprintln!(
+        "[ttyd] Serving at {url} (pid {}). Press Ctrl-C to stop.",
+        child.id()
+    );
+    if local != ttyd.bind {
+        eprintln!("[ttyd] Listening on all interfaces ({}).", ttyd.bind);
+    }
+
+    let mut run = TtydRun {
+        child: &mut child,
+        host,
+        sock: format!("ttyd-{session}"),
+        session,
+        ascii_out,
+    };
+    let Some(rc) = wait_for_stop(&mut run, &presses) else {
+        if let Some(w) = window.as_mut() {
+            let _ = w.kill();
         }
+        std::process::exit(130);
+    };
+
+    if let Some(w) = window.as_mut() {
+        let _ = w.kill();
+        let _ = w.wait();
+    }
+    report_cast(ascii_out);
+    rc
+}
+
+const POLL: Duration = Duration::from_millis(100);
+
+/// Count Ctrl-C presses in `presses`, with SIGTERM and SIGHUP (a closed
+/// terminal) counting as one; [`wait_for_stop`] does the shutdown.
+fn count_stop_signals(presses: &Arc<AtomicUsize>) {
+    let presses = presses.clone();
+    ctrlc::set_handler(move || {
+        presses.fetch_add(1, Ordering::SeqCst);
     })
     .ok();
+}
 
-    let url = format!("http://127.0.0.1:{port}/");
-    eprintln!("[ttyd] Waiting for {url} ...");
-    wait_for_tcp("127.0.0.1", port, 100, 50);
+/// A running ttyd and its recording, as [`wait_for_stop`] sees them.
+trait Recording {
+    /// The exit code, once the server has exited.
+    fn try_wait(&mut self) -> io::Result<Option<i32>>;
+    /// Ask the recorder to finish the cast (detach tmux).
+    fn detach(&mut self);
+    fn has_clients(&self) -> bool;
+    fn cast_len(&self) -> Option<u64>;
+    /// Stop the server (SIGTERM).
+    fn terminate(&mut self);
+    /// Kill the server and wait for it.
+    fn kill(&mut self);
+}
+
+struct TtydRun<'a> {
+    child: &'a mut Child,
+    host: &'a Host,
+    sock: String,
+    session: &'a str,
+    ascii_out: &'a Path,
+}
 
-    // optional browser open
-    if which::which("xdg-open").is_ok() {
-        let _ = Command::new("xdg-open")
-            .arg(&url)
+impl Recording for TtydRun<'_> {
+    fn try_wait(&mut self) -> io::Result<Option<i32>> {
+        Ok(self.child.try_wait()?.map(|s| s.code().unwrap_or(1)))
+    }
+
+    fn detach(&mut self) {
+        let _ = self
+            .host
+            .command(
+                "tmux",
+                &["-L", &self.sock, "detach-client", "-s", self.session],
+            )
             .stdout(Stdio::null())
             .stderr(Stdio::null())
-            .spawn();
+            .status();
     }
 
+    fn has_clients(&self) -> bool {
+        tmux_has_clients(self.host, &self.sock)
+    }
+
+    fn cast_len(&self) -> Option<u64> {
+        std::fs::metadata(self.ascii_out).map(|m| m.len()).ok()
+    }
+
+    fn terminate(&mut self) {
+        terminate(self.child.id());
+    }
+
+    fn kill(&mut self) {
+        kill(self.child.id());
+        let _ = self.child.wait();
+    }
+}
+
+/// Wait for the server to exit, stopping it gracefully after one Ctrl-C
+/// (`presses`) and killing it after two. The exit code, or `None` if it was
+/// killed.
+fn wait_for_stop(run: &mut impl Recording, presses: &AtomicUsize) -> Option<i32> {
+    let mut stop: Option<GracefulStop> = None;
+    loop {
+        match run.try_wait() {
+            Ok(Some(code)) => return Some(code),
+            Ok(None) => {}
+            Err(e) => {
+                eprintln_err(&format!("Failed waiting for ttyd: {e}"));
+                return Some(1);
+            }
+        }
+        match presses.load(Ordering::SeqCst) {
+            0 => {}
+            1 if stop.is_none() => {
+                eprintln!(
+                    "\n[ttyd] Stopping: detaching tmux so the recorder can finish the cast (Ctrl-C again to force)..."
+                );
+                run.detach();
+                stop = Some(GracefulStop::new());
+            }
+            1 => {
+                let stop = stop.as_mut().unwrap();
+                if stop.poll(run.has_clients(), run.cast_len()) {
+                    run.terminate();
+                }
+            }
+            _ => {
+                eprintln!("\n[ttyd] Forced exit; the cast may be incomplete.");
+                run.kill();
+                return None;
+            }
+        }
+        thread::sleep(POLL);
+    }
+}
+
+/// Tracks a Ctrl-C shutdown: once no tmux client is attached and the cast
+/// has stopped growing, the recorder is done and ttyd can be stopped.
+struct GracefulStop {
+    started: Instant,
+    last_len: Option<u64>,
+    stable_since: Instant,
+    terminated: bool,
+}
+
+impl GracefulStop {
+    const SETTLE: Duration = Duration::from_millis(500);
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn new() -> Self {
+        let now = Instant::now();
+        GracefulStop {
+            started: now,
+            last_len: None,
+            stable_since: now,
+            terminated: false,
+        }
+    }
+
+    /// Returns true (once) when it is time to stop ttyd, given whether tmux
+    /// still has clients and the size of the cast.
+    fn poll(&mut self, has_clients: bool, len: Option<u64>) -> bool {
+        if self.terminated {
+            return false;
+        }
+        if len != self.last_len {
+            self.last_len = len;
+            self.stable_since = Instant::now();
+        }
+        let settled = !has_clients && self.stable_since.elapsed() >= Self::SETTLE;
+        if settled || self.started.elapsed() >= Self::TIMEOUT {
+            if !settled {
+                eprintln!("[ttyd] Timed out waiting for the cast to be flushed.");
+            }
+            self.terminated = true;
+            return true;
+        }
+        false
+    }
+}
+
+fn tmux_has_clients(host: &Host, sock: &str) -> bool {
+    host.command("tmux", &["-L", sock, "list-clients"])
+        .stderr(Stdio::null())
+        .output()
+        .map(|o| o.status.success() && !o.stdout.is_empty())
+        .unwrap_or(false)
+}
+
+fn terminate(pid: u32) {
+    signal(pid, false);
+}
+
+fn kill(pid: u32) {
+    signal(pid, true);
+}
+
+fn signal(pid: u32, force: bool) {
+    #[cfg(unix)]
+    {
+        let sig = if force { libc::SIGKILL } else { libc::SIGTERM };
+        let _ = unsafe { libc::kill(pid as i32, sig) };
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (pid, force);
+    }
+}
+
+/// Print where the recording went, and how long and big it is.
+fn report_cast(ascii_out: &Path) {
+    let Ok(md) = std::fs::metadata(ascii_out) else {
+        eprintln!("[ttyd] No cast was written to {}", ascii_out.display());
+        return;
+    };
+    let duration = cast::read_info(ascii_out)
+        .map(|i| i.duration)
+        .unwrap_or(0.0);
     eprintln!(
-        "[ttyd] Serving at {url} (pid {}). Press Ctrl-C to stop.",
-        child.id()
+        "[ttyd] Saved {} ({}, {})",
+        ascii_out.display(),
+        human_duration(duration),
+        human_size(md.len())
     );
-    match child.wait() {
-        Ok(status) => status.code().unwrap_or(1),
-        Err(e) => {
-            eprintln_err(&format!("Failed waiting for ttyd: {e}"));
-            1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_sockets_are_listed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        for name in ["ttyd-demo", "default", "ttyd-a", "other-ttyd-x"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        assert_eq!(ttyd_sockets_in(dir), ["ttyd-a", "ttyd-demo"]);
+        assert!(ttyd_sockets_in(&dir.join("missing")).is_empty());
+    }
+
+    #[test]
+    fn local_mounts_are_absolute() {
+        let mount = mount_dir(&Host::Local, Path::new("proj"));
+        assert_eq!(mount, std::env::current_dir().unwrap().join("proj"));
+        let remote = Host::Ssh {
+            ssh: "ssh".into(),
+            dest: "me@lab".into(),
+        };
+        assert_eq!(mount_dir(&remote, Path::new("proj")), Path::new("proj"));
+    }
+
+    #[test]
+    fn container_shell_starts_only_with_a_new_session() {
+        if !crate::util::deps::have("tmux") {
+            return;
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let runtime = dir.join("runtime");
+        std::fs::write(
+            &runtime,
+            "#!/bin/sh\nprintf '%s\\n' \"$@\" > \"$0.args\"\nexec sleep 30\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&runtime, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let container = Container {
+            runtime: runtime.to_string_lossy().into(),
+            image: "debian:stable".into(),
+        };
+        let sock = format!("ttyd-test-{}", std::process::id());
+        let shell = container.shell_command("demo", Some(dir));
+        let prepare = || {
+            prepare_session(
+                &Host::Local,
+                &sock,
+                "demo",
+                80,
+                24,
+                Some(dir),
+                Some(&shell),
+            )
+        };
+
+        assert!(prepare().unwrap());
+        let args = dir.join("runtime.args");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !args.is_file() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        let mount = format!("{}:/work", dir.display());
+        assert_eq!(
+            std::fs::read_to_string(&args)
+                .unwrap()
+                .lines()
+                .collect::<Vec<_>>(),
+            [
+                "run",
+                "--rm",
+                "-it",
+                "--name",
+                "shell-scene-demo",
+                "--hostname",
+                "demo",
+                "-v",
+                &mount,
+                "-w",
+                "/work",
+                "debian:stable",
+                "bash",
+                "-l"
+            ]
+        );
+        assert!(!prepare().unwrap());
+        let _ = run_tmux(&["-L", &sock, "kill-server"]);
+    }
+
+    /// A server that exits when terminated, with tmux keeping its client
+    /// until detached.
+    #[derive(Default)]
+    struct FakeRun {
+        calls: Vec<&'static str>,
+        detached: bool,
+        len: u64,
+        exited: bool,
+    }
+
+    impl Recording for FakeRun {
+        fn try_wait(&mut self) -> io::Result<Option<i32>> {
+            Ok(self.exited.then_some(0))
+        }
+        fn detach(&mut self) {
+            self.calls.push("detach");
+            self.detached = true;
+        }
+        fn has_clients(&self) -> bool {
+            !self.detached
         }
+        fn cast_len(&self) -> Option<u64> {
+            Some(self.len)
+        }
+        fn terminate(&mut self) {
+            self.calls.push("terminate");
+            self.exited = true;
+        }
+        fn kill(&mut self) {
+            self.calls.push("kill");
+        }
+    }
+
+    /// A real server process, with no tmux or cast.
+    struct ChildRun(Child);
+
+    impl Recording for ChildRun {
+        fn try_wait(&mut self) -> io::Result<Option<i32>> {
+            Ok(self.0.try_wait()?.map(|s| s.code().unwrap_or(1)))
+        }
+        fn detach(&mut self) {}
+        fn has_clients(&self) -> bool {
+            false
+        }
+        fn cast_len(&self) -> Option<u64> {
+            None
+        }
+        fn terminate(&mut self) {
+            terminate(self.0.id());
+        }
+        fn kill(&mut self) {
+            kill(self.0.id());
+            let _ = self.0.wait();
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sigterm_stops_the_server() {
+        use std::os::unix::process::CommandExt;
+        let presses = Arc::new(AtomicUsize::new(0));
+        count_stop_signals(&presses);
+        let child = Command::new("sleep")
+            .arg("30")
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let mut run = ChildRun(child);
+        unsafe { libc::kill(libc::getpid(), libc::SIGTERM) };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while presses.load(Ordering::SeqCst) == 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(presses.load(Ordering::SeqCst), 1);
+        assert_eq!(wait_for_stop(&mut run, &presses), Some(1));
+        assert!(run.0.try_wait().unwrap().is_some());
+    }
+
+    #[test]
+    fn ctrl_c_detaches_then_stops_once_the_cast_settles() {
+        let mut run = FakeRun::default();
+        let presses = AtomicUsize::new(1);
+        assert_eq!(wait_for_stop(&mut run, &presses), Some(0));
+        assert_eq!(run.calls, ["detach", "terminate"]);
+
+        let mut run = FakeRun::default();
+        let presses = AtomicUsize::new(2);
+        assert_eq!(wait_for_stop(&mut run, &presses), None);
+        assert_eq!(run.calls, ["kill"]);
+
+        // the server exiting on its own needs no Ctrl-C
+        let mut run = FakeRun {
+            exited: true,
+            ..Default::default()
+        };
+        assert_eq!(wait_for_stop(&mut run, &AtomicUsize::new(0)), Some(0));
+        assert!(run.calls.is_empty());
     }
 }
@@ -0,0 +1,157 @@
+//! Per-project pronunciation dictionary (`pronunciation.toml`): how to say
+//! words the voices get wrong.
+//!
+//! ```toml
+//! [[rule]]
+//! match = "Traefik"          # whole word, any case
+//! say = "traffic"
+//!
+//! [[rule]]
+//! regex = '\b(\d)(\d)(\d)\b'
+//! say = "$1 $2 $3"
+//!
+//! [[rule]]
+//! match = "Traefik"
+//! say = "tray fick"
+//! voice = "am_adam"          # applied before the rules for every voice
+//! ```
+
+use regex::{NoExpand, Regex};
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    #[serde(default)]
+    rule: Vec<RawRule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    #[serde(rename = "match")]
+    literal: Option<String>,
+    regex: Option<String>,
+    say: String,
+    voice: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Regex,
+    /// `say` of a `match` rule is used as is; of a `regex` rule, `$1` etc.
+    /// are expanded.
+    expand: bool,
+    say: String,
+    voice: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    rules: Vec<Rule>,
+}
+
+impl Dictionary {
+    /// The dictionary at `path`; empty if there is no such file.
+    pub fn load(path: &Path) -> io::Result<Dictionary> {
+        let src = match fs::read_to_string(path) {
+            Ok(src) => src,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Dictionary::default()),
+            Err(e) => return Err(e),
+        };
+        Dictionary::parse(&src).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        })
+    }
+
+    fn parse(src: &str) -> Result<Dictionary, String> {
+        let file: File = toml::from_str(src).map_err(|e| e.message().to_string())?;
+        let rules = file
+            .rule
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let (pattern, expand) = match (r.literal, r.regex) {
+                    (Some(lit), None) => (literal_pattern(&lit), false),
+                    (None, Some(re)) => (re, true),
+                    _ => return Err(format!("rule {}: needs one of `match` or `regex`", i + 1)),
+                };
+                let pattern = Regex::new(&pattern).map_err(|e| format!("rule {}: {e}", i + 1))?;
+                Ok(Rule {
+                    pattern,
+                    expand,
+                    say: r.say,
+                    voice: r.voice,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Dictionary { rules })
+    }
+
+    /// `text` as `voice` should say it: the rules for `voice`, then the rules
+    /// for every voice, each in file order.
+    pub fn apply(&self, text: &str, voice: &str) -> String {
+        let for_voice = self
+            .rules
+            .iter()
+            .filter(|r| r.voice.as_deref() == Some(voice));
+        let for_all = self.rules.iter().filter(|r| r.voice.is_none());
+        for_voice
+            .chain(for_all)
+            .fold(text.to_string(), |acc, r| match r.expand {
+                true => r.pattern.replace_all(&acc, r.say.as_str()).into_owned(),
+                false => r.pattern.replace_all(&acc, NoExpand(&r.say)).into_owned(),
+            })
+    }
+}
+
+/// `lit`, case-insensitive, not matching inside a longer word.
+fn literal_pattern(lit: &str) -> String {
+    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let start = if word(lit.chars().next()) { r"\b" } else { "" };
+    let end = if word(lit.chars().last()) { r"\b" } else { "" };
+    format!("(?i){start}{}{end}", regex::escape(lit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_apply_per_voice_then_for_all() {
+        let dict = Dictionary::parse(
+            r#"
+            [[rule]]
+            match = "d.rymcg.tech"
+            say = "dee dot rye mic gee dot tech"
+            [[rule]]
+            match = "Traefik"
+            say = "traffic"
+            [[rule]]
+            regex = '\b(\d)(\d)(\d)\b'
+            say = "$1 $2 $3"
+            [[rule]]
+            match = "traefik"
+            say = "tray fick"
+            voice = "am_adam"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            dict.apply("TRAEFIK on d.rymcg.tech:443, not Traefiks", "af_heart"),
+            "traffic on dee dot rye mic gee dot tech:4 4 3, not Traefiks"
+        );
+        assert_eq!(dict.apply("Traefik", "am_adam"), "tray fick");
+        assert!(
+            Dictionary::parse("[[rule]]\nsay = \"x\"\n")
+                .unwrap_err()
+                .contains("rule 1")
+        );
+    }
+}
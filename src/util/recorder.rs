@@ -0,0 +1,275 @@
+use clap::ValueEnum;
+use portable_pty::{CommandBuilder, PtySize, native_pty_system};
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// What writes the cast.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Recorder {
+    /// Built in: runs the command in a PTY and writes asciicast v2 itself.
+    Native,
+    /// `asciinema rec`.
+    #[default]
+    Asciinema,
+}
+
+impl Recorder {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Recorder::Native => "native",
+            Recorder::Asciinema => "asciinema",
+        }
+    }
+}
+
+/// Run `cmdline` (with `sh -c`) in a `cols`x`rows` PTY, mirroring it to this
+/// terminal and recording its output to `ascii_out` as asciicast v2. When
+/// appending, event times continue from `offset`. When this terminal is
+/// resized, so is the PTY, and the cast gets an `r` event. Returns the exit
+/// code.
+pub fn record(
+    ascii_out: &Path,
+    cols: u32,
+    rows: u32,
+    append: bool,
+    offset: f64,
+    cmdline: &str,
+) -> io::Result<i32> {
+    let pty = native_pty_system()
+        .openpty(PtySize {
+            cols: cols.try_into().unwrap_or(u16::MAX),
+            rows: rows.try_into().unwrap_or(u16::MAX),
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let mut cmd = CommandBuilder::new("sh");
+    cmd.args(["-c", cmdline]);
+    cmd.cwd(std::env::current_dir()?);
+    let mut child = pty
+        .slave
+        .spawn_command(cmd)
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    drop(pty.slave);
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(ascii_out)?;
+    let mut cast = BufWriter::new(file);
+    if !append {
+        writeln!(cast, "{}", header(cols, rows))?;
+        cast.flush()?;
+    }
+
+    let mut reader = pty
+        .master
+        .try_clone_reader()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let mut writer = pty
+        .master
+        .take_writer()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let _raw = RawMode::enable();
+
+    // keystrokes go straight to the PTY; the thread ends with the process
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut buf = [0u8; 1024];
+        while let Ok(n) = stdin.read(&mut buf) {
+            if n == 0 || writer.write_all(&buf[..n]).is_err() {
+                break;
+            }
+        }
+    });
+
+    // output is read on its own thread so resizes are noticed between reads
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                // EIO once the last process on the PTY exits
+                Err(_) => break,
+            }
+        }
+    });
+
+    let started = Instant::now();
+    let mut stdout = io::stdout().lock();
+    let mut pending = Vec::new();
+    let mut size = terminal_size();
+    loop {
+        let chunk = match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(chunk) => Some(chunk),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let time = offset + started.elapsed().as_secs_f64();
+        let now = terminal_size();
+        if let Some((cols, rows)) = now
+            && now != size
+        {
+            size = now;
+            let _ = pty.master.resize(PtySize {
+                cols,
+                rows,
+                pixel_width: 0,
+                pixel_height: 0,
+            });
+            writeln!(cast, "{}", event(time, "r", &format!("{cols}x{rows}")))?;
+            cast.flush()?;
+        }
+        let Some(chunk) = chunk else {
+            continue;
+        };
+        let _ = stdout.write_all(&chunk);
+        let _ = stdout.flush();
+        let text = decode_utf8(&mut pending, &chunk);
+        if !text.is_empty() {
+            writeln!(cast, "{}", event(time, "o", &text))?;
+            cast.flush()?;
+        }
+    }
+
+    let status = child.wait()?;
+    Ok(status.exit_code() as i32)
+}
+
+fn header(cols: u32, rows: u32) -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut env = serde_json::Map::new();
+    for key in ["SHELL", "TERM"] {
+        if let Ok(v) = std::env::var(key) {
+            env.insert(key.into(), v.into());
+        }
+    }
+    serde_json::json!({
+        "version": 2,
+        "width": cols,
+        "height": rows,
+        "timestamp": timestamp,
+        "env": env,
+    })
+    .to_string()
+}
+
+fn event(time: f64, kind: &str, data: &str) -> String {
+    let time = (time * 1e6).round() / 1e6;
+    serde_json::json!([time, kind, data]).to_string()
+}
+
+/// Columns and rows of the terminal on stdin, if it is one.
+fn terminal_size() -> Option<(u16, u16)> {
+    #[cfg(unix)]
+    unsafe {
+        let mut ws: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(0, libc::TIOCGWINSZ, &mut ws) == 0 && ws.ws_col > 0 && ws.ws_row > 0 {
+            return Some((ws.ws_col, ws.ws_row));
+        }
+    }
+    None
+}
+
+/// Decode `chunk` after the bytes left `pending` from the previous read. An
+/// incomplete sequence at the end is kept for the next chunk; invalid bytes
+/// become U+FFFD.
+fn decode_utf8(pending: &mut Vec<u8>, chunk: &[u8]) -> String {
+    pending.extend_from_slice(chunk);
+    let mut out = String::new();
+    let mut rest: &[u8] = pending;
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(s) => {
+                out.push_str(s);
+                rest = &[];
+                break;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                match e.error_len() {
+                    Some(n) => {
+                        out.push(char::REPLACEMENT_CHARACTER);
+                        rest = &after[n..];
+                    }
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    *pending = rest.to_vec();
+    out
+}
+
+/// Raw mode on stdin while recording, so keys reach the PTY unprocessed.
+struct RawMode {
+    #[cfg(unix)]
+    saved: Option<libc::termios>,
+}
+
+impl RawMode {
+    fn enable() -> RawMode {
+        #[cfg(unix)]
+        unsafe {
+            let mut term: libc::termios = std::mem::zeroed();
+            if libc::isatty(0) == 0 || libc::tcgetattr(0, &mut term) != 0 {
+                return RawMode { saved: None };
+            }
+            let saved = term;
+            libc::cfmakeraw(&mut term);
+            libc::tcsetattr(0, libc::TCSANOW, &term);
+            RawMode { saved: Some(saved) }
+        }
+        #[cfg(not(unix))]
+        RawMode {}
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(saved) = &self.saved {
+            unsafe {
+                libc::tcsetattr(0, libc::TCSANOW, saved);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_utf8_across_reads() {
+        let mut pending = Vec::new();
+        let bytes = "é→".as_bytes();
+        assert_eq!(decode_utf8(&mut pending, &bytes[..1]), "");
+        assert_eq!(decode_utf8(&mut pending, &bytes[1..3]), "é");
+        assert_eq!(decode_utf8(&mut pending, &bytes[3..]), "→");
+        assert_eq!(decode_utf8(&mut pending, b"a\xffb"), "a\u{fffd}b");
+        assert!(pending.is_empty());
+        assert_eq!(event(1.23456789, "o", "x\n"), r#"[1.234568,"o","x\n"]"#);
+        assert_eq!(event(2.0, "r", "100x30"), r#"[2.0,"r","100x30"]"#);
+    }
+}
@@ -0,0 +1,196 @@
+//! SceneScript (`.timeline`) parser; the same language as the player's
+//! `scenescript.ts`, producing the same `{ name, items }` JSON.
+
+use regex::Regex;
+use serde_json::{Map, Value, json};
+use std::sync::LazyLock;
+
+const DEFAULT_VOICE: &str = "af_heart";
+const DEFAULT_ANCHOR: &str = "inBegin";
+
+static TIMELINE: LazyLock<Regex> = LazyLock::new(|| re(r"^timeline\s*:\s*(.+)\s*$"));
+static FADE: LazyLock<Regex> = LazyLock::new(|| re(r"^(fade|crossfade)\s+([0-9]+)\s*$"));
+static SWIPE: LazyLock<Regex> =
+    LazyLock::new(|| re(r"^swipe\s+(left|right|up|down)\s+([0-9]+)\s*$"));
+static CARD: LazyLock<Regex> =
+    LazyLock::new(|| re(r#"^card\s+"([^"]+)"(?:\s*\|\s*"([^"]+)")?(?:\s*@\s*([0-9.]+)s?)?\s*$"#));
+static CAST: LazyLock<Regex> = LazyLock::new(|| re(r"^cast\s+(\S+)\s+([0-9]+)x([0-9]+)\s*$"));
+static SAY: LazyLock<Regex> = LazyLock::new(|| {
+    re(concat!(
+        r#"^\s{2,}say\s+"([\s\S]*?)"(?:\s*@\s*([0-9.]+)s?)?(?:\s+voice=([a-z0-9_]+))?"#,
+        r#"(?:\s+anchor=(inBegin|inEnd|clipStart|baseEnd|visibleEnd))?(?:\s+id=([a-z0-9_-]+))?\s*$"#
+    ))
+});
+
+fn re(pattern: &str) -> Regex {
+    Regex::new(&format!("(?i){pattern}")).expect("valid pattern")
+}
+
+/// A parsed timeline: `{ "name": ..., "items": [...] }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    pub name: String,
+    pub items: Vec<Value>,
+}
+
+impl Timeline {
+    pub fn to_json(&self) -> Value {
+        json!({ "name": self.name, "items": self.items })
+    }
+}
+
+/// A line that isn't SceneScript (1-based line number).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: SceneScript syntax error: {:?}",
+            self.line, self.text
+        )
+    }
+}
+
+/// JavaScript's `Number(s)`, which the player's parser uses: `null` (NaN in
+/// JSON) for anything that isn't a number, and whole numbers without `.0`.
+fn number(s: Option<&str>) -> Value {
+    match s.and_then(|s| s.parse::<f64>().ok()) {
+        Some(n) if n.fract() == 0.0 && n.abs() < 1e15 => json!(n as i64),
+        Some(n) => json!(n),
+        None => Value::Null,
+    }
+}
+
+fn integer(s: &str) -> Value {
+    s.parse::<u64>().map_or(Value::Null, |n| json!(n))
+}
+
+pub fn parse(input: &str) -> Result<Timeline, SyntaxError> {
+    let mut name = "timeline".to_string();
+    let mut items = Vec::new();
+    // the open card or cast, which `say` lines attach to
+    let mut current: Option<Map<String, Value>> = None;
+
+    for (i, raw) in input.lines().enumerate() {
+        let line = raw.replace('\t', "  ");
+        let line = line.trim_end();
+        if line.trim().is_empty() || line.trim().starts_with('#') {
+            continue;
+        }
+        if let Some(c) = TIMELINE.captures(line) {
+            name = c[1].trim().to_string();
+            continue;
+        }
+        if let Some(c) = FADE.captures(line) {
+            items.extend(current.take().map(Value::Object));
+            items.push(json!({
+                "type": "transition",
+                "name": c[1].to_lowercase(),
+                "durationFrames": integer(&c[2]),
+            }));
+            continue;
+        }
+        if let Some(c) = SWIPE.captures(line) {
+            items.extend(current.take().map(Value::Object));
+            items.push(json!({
+                "type": "transition",
+                "name": "swipe",
+                "direction": c[1].to_lowercase(),
+                "durationFrames": integer(&c[2]),
+            }));
+            continue;
+        }
+        if let Some(c) = CARD.captures(line) {
+            items.extend(current.take().map(Value::Object));
+            let mut card = Map::new();
+            card.insert("type".into(), json!("card"));
+            card.insert("title".into(), json!(&c[1]));
+            if let Some(sub) = c.get(2) {
+                card.insert("subtitle".into(), json!(sub.as_str()));
+            }
+            card.insert("seconds".into(), number(c.get(3).map(|m| m.as_str())));
+            card.insert("speech".into(), json!([]));
+            current = Some(card);
+            continue;
+        }
+        if let Some(c) = CAST.captures(line) {
+            items.extend(current.take().map(Value::Object));
+            let cast = json!({
+                "type": "cast",
+                "castPath": &c[1],
+                "cols": integer(&c[2]),
+                "rows": integer(&c[3]),
+                "speech": [],
+            });
+            current = cast.as_object().cloned();
+            continue;
+        }
+        if let Some(open) = current.as_mut()
+            && let Some(c) = SAY.captures(line)
+        {
+            let mut cue = Map::new();
+            cue.insert("text".into(), json!(&c[1]));
+            let voice = c.get(3).map_or(DEFAULT_VOICE, |m| m.as_str());
+            cue.insert("voice".into(), json!(voice));
+            let anchor = c.get(4).map_or(DEFAULT_ANCHOR, |m| m.as_str());
+            cue.insert("anchor".into(), json!(anchor));
+            if let Some(off) = c.get(2) {
+                cue.insert("offsetSec".into(), number(Some(off.as_str())));
+            }
+            if let Some(id) = c.get(5) {
+                cue.insert("id".into(), json!(id.as_str()));
+            }
+            if let Some(Value::Array(speech)) = open.get_mut("speech") {
+                speech.push(Value::Object(cue));
+            }
+            continue;
+        }
+        return Err(SyntaxError {
+            line: i + 1,
+            text: line.to_string(),
+        });
+    }
+    items.extend(current.map(Value::Object));
+    Ok(Timeline { name, items })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_like_the_player() {
+        let src = "# demo\ntimeline: Demo Talk\n\
+                   card \"Hello\" | \"world\" @ 3s\n\
+                   \tsay \"Hi \"there\"\" @ 0.5 voice=am_adam\n\
+                   fade 15\n\
+                   cast casts/demo.cast 80x24\n  say \"Watch.\" anchor=inEnd id=watch-1\n\
+                   swipe Left 10\n";
+        let tl = parse(src).unwrap();
+        assert_eq!(tl.name, "Demo Talk");
+        assert_eq!(
+            Value::Array(tl.items),
+            json!([
+                {
+                    "type": "card", "title": "Hello", "subtitle": "world", "seconds": 3,
+                    "speech": [{ "text": "Hi \"there\"", "voice": "am_adam", "anchor": "inBegin", "offsetSec": 0.5 }]
+                },
+                { "type": "transition", "name": "fade", "durationFrames": 15 },
+                {
+                    "type": "cast", "castPath": "casts/demo.cast", "cols": 80, "rows": 24,
+                    "speech": [{ "text": "Watch.", "voice": "af_heart", "anchor": "inEnd", "id": "watch-1" }]
+                },
+                { "type": "transition", "name": "swipe", "direction": "left", "durationFrames": 10 }
+            ])
+        );
+        assert_eq!(
+            parse("say \"orphan\"").unwrap_err().to_string(),
+            "line 1: SceneScript syntax error: \"say \\\"orphan\\\"\""
+        );
+    }
+}
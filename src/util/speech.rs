@@ -0,0 +1,804 @@
+use crate::util::compile::{self, Project};
+use crate::util::pronunciation::Dictionary;
+use crate::util::{deps, fsx};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// Audio file format of the synthesized speech.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Opus,
+    Vorbis,
+    /// The TTS output as is; no ffmpeg needed.
+    Wav,
+}
+
+impl Format {
+    pub fn ext(self) -> &'static str {
+        match self {
+            Format::Opus => "opus",
+            Format::Vorbis => "ogg",
+            Format::Wav => "wav",
+        }
+    }
+
+    fn encoder_args(self) -> &'static [&'static str] {
+        match self {
+            Format::Opus => &["-c:a", "libopus", "-b:a", "96k", "-vbr", "on"],
+            Format::Vorbis => &["-c:a", "libvorbis", "-q:a", "5"],
+            Format::Wav => &[],
+        }
+    }
+}
+
+/// How `build` (and `serve`) synthesize speech.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub backend: Backend,
+    pub format: Format,
+    /// For [`Backend::Command`].
+    pub command: Option<String>,
+    /// For [`Backend::Files`]. Default: PLAYER/voiceover
+    pub voiceover: Option<PathBuf>,
+    /// Default: PLAYER/pronunciation.toml
+    pub pronunciation: Option<PathBuf>,
+}
+
+impl Options {
+    fn tts(&self, project: &Project) -> io::Result<Box<dyn Tts>> {
+        Ok(match self.backend {
+            Backend::Kokoro => Box::new(Kokoro::new(&project.player)),
+            Backend::Command => Box::new(Shell {
+                command: self.command.clone().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--tts command needs --tts-command",
+                    )
+                })?,
+                dir: project.player.clone(),
+            }),
+            Backend::Files => Box::new(Files {
+                dir: self
+                    .voiceover
+                    .clone()
+                    .unwrap_or_else(|| project.player.join("voiceover")),
+            }),
+            Backend::Stub => Box::new(Stub),
+        })
+    }
+}
+
+/// The project's pronunciation dictionary: `path`, else
+/// PLAYER/pronunciation.toml.
+pub fn dictionary(project: &Project, path: Option<&Path>) -> io::Result<Dictionary> {
+    match path {
+        Some(p) => Dictionary::load(p),
+        None => Dictionary::load(&project.player.join("pronunciation.toml")),
+    }
+}
+
+/// A `say` cue of a timeline.
+#[derive(Debug, Clone, Copy)]
+pub struct Cue<'a> {
+    pub timeline: &'a str,
+    pub id: Option<&'a str>,
+    /// The text to synthesize, with pronunciation applied.
+    pub text: &'a str,
+    /// The text as written in the timeline.
+    pub written: &'a str,
+    pub voice: &'a str,
+}
+
+/// Turns cues into WAV files.
+pub trait Tts {
+    /// Mixed into the cache key of a cue's audio, so audio from another
+    /// backend (or another recording) isn't reused. Kokoro's is empty, so
+    /// its keys are those the player's speech script made for the same
+    /// spoken text.
+    fn cache_tag(&self, _cue: &Cue) -> io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn synthesize(&mut self, cue: &Cue, wav: &Path) -> io::Result<()>;
+}
+
+/// Which [`Tts`] to use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Backend {
+    /// Kokoro (kokoro-js, run with the player project's node modules).
+    #[default]
+    Kokoro,
+    /// `--tts-command`, given the text on stdin, writing a WAV to stdout.
+    Command,
+    /// Recorded voiceover: `VOICEOVER/TIMELINE/ID.wav` for each cue.
+    Files,
+    /// Silence as long as the text would take to say; for tests and drafts.
+    Stub,
+}
+
+pub struct Stub;
+
+impl Tts for Stub {
+    fn cache_tag(&self, _cue: &Cue) -> io::Result<String> {
+        Ok("stub".into())
+    }
+
+    fn synthesize(&mut self, cue: &Cue, wav: &Path) -> io::Result<()> {
+        let secs = (cue.text.chars().count() as f64 * 0.06).max(0.5);
+        fs::write(wav, silent_wav(secs))
+    }
+}
+
+/// A shell command (piper, espeak-ng, a script...) run in the player
+/// project for each cue. Its stdin is one line of JSON,
+/// `{"text", "voice", "timeline", "id"}`, and it writes the WAV to stdout.
+/// For one-liners the text and voice are also in `$SPEECH_TEXT` and
+/// `$SPEECH_VOICE`.
+pub struct Shell {
+    command: String,
+    dir: PathBuf,
+}
+
+impl Tts for Shell {
+    fn cache_tag(&self, _cue: &Cue) -> io::Result<String> {
+        Ok(format!("command:{}", self.command))
+    }
+
+    fn synthesize(&mut self, cue: &Cue, wav: &Path) -> io::Result<()> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .current_dir(&self.dir)
+            .env("SPEECH_TEXT", cue.text)
+            .env("SPEECH_VOICE", cue.voice)
+            .env("SPEECH_TIMELINE", cue.timeline)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let mut stdin = child.stdin.take().unwrap();
+        let request = json!({
+            "text": cue.text,
+            "voice": cue.voice,
+            "timeline": cue.timeline,
+            "id": cue.id,
+        });
+        // written from another thread so a command that streams its output
+        // before reading all input can't deadlock us
+        let writer = std::thread::spawn(move || writeln!(stdin, "{request}"));
+        let out = child.wait_with_output()?;
+        let _ = writer.join();
+        if !out.status.success() {
+            return Err(io::Error::other(format!(
+                "TTS command failed ({})",
+                out.status
+            )));
+        }
+        if !out.stdout.starts_with(b"RIFF") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "TTS command didn't write a WAV to stdout",
+            ));
+        }
+        fs::write(wav, out.stdout)
+    }
+}
+
+/// Pre-recorded voiceover, one WAV per cue: `DIR/TIMELINE/ID.wav`, where
+/// `ID` is the cue's `id=`, else the slug of its text as written (the
+/// pronunciation dictionary is for voices, not people) and its voice.
+pub struct Files {
+    dir: PathBuf,
+}
+
+impl Files {
+    fn recording(&self, cue: &Cue) -> PathBuf {
+        let id = match cue.id {
+            Some(id) => id.to_string(),
+            None => format!("{}-{}", file_slug(cue.written), cue.voice),
+        };
+        self.dir.join(cue.timeline).join(format!("{id}.wav"))
+    }
+
+    fn read(&self, cue: &Cue) -> io::Result<Vec<u8>> {
+        let path = self.recording(cue);
+        fs::read(&path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "no voiceover for {:?} ({}: {e})",
+                    cue.written,
+                    path.display()
+                ),
+            )
+        })
+    }
+}
+
+impl Tts for Files {
+    fn cache_tag(&self, cue: &Cue) -> io::Result<String> {
+        // re-recording a line replaces its audio
+        Ok(format!("files:{}", fsx::short_hash(&self.read(cue)?)))
+    }
+
+    fn synthesize(&mut self, cue: &Cue, wav: &Path) -> io::Result<()> {
+        fs::write(wav, self.read(cue)?)
+    }
+}
+
+/// A 24 kHz, 16-bit mono WAV of `secs` of silence.
+fn silent_wav(secs: f64) -> Vec<u8> {
+    const RATE: u32 = 24_000;
+    let data_len = (secs * RATE as f64) as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&RATE.to_le_bytes());
+    wav.extend_from_slice(&(RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.resize(44 + data_len as usize, 0);
+    wav
+}
+
+/// `scripts/tts-kokoro.ts` in the player project, started on first use and
+/// kept running so the model loads once. It reads one JSON request per line
+/// and answers each with a line.
+pub struct Kokoro {
+    player: PathBuf,
+    process: Option<(Child, ChildStdin, BufReader<ChildStdout>)>,
+}
+
+impl Kokoro {
+    fn new(player: &Path) -> Kokoro {
+        Kokoro {
+            player: player.to_path_buf(),
+            process: None,
+        }
+    }
+}
+
+impl Tts for Kokoro {
+    fn synthesize(&mut self, cue: &Cue, wav: &Path) -> io::Result<()> {
+        if self.process.is_none() {
+            let mut child = Command::new("npx")
+                .args(["--no-install", "tsx", "scripts/tts-kokoro.ts"])
+                .current_dir(&self.player)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .map_err(|e| io::Error::other(format!("failed to start Kokoro (npx): {e}")))?;
+            let stdin = child.stdin.take().unwrap();
+            let stdout = BufReader::new(child.stdout.take().unwrap());
+            self.process = Some((child, stdin, stdout));
+        }
+        let (_, stdin, stdout) = self.process.as_mut().unwrap();
+        let wav = std::path::absolute(wav)?;
+        let request = json!({ "text": cue.text, "voice": cue.voice, "out": wav });
+        writeln!(stdin, "{request}")?;
+        stdin.flush()?;
+        let mut reply = String::new();
+        if stdout.read_line(&mut reply)? == 0 {
+            return Err(io::Error::other("Kokoro exited"));
+        }
+        match reply.trim() {
+            "ok" => Ok(()),
+            err => Err(io::Error::other(format!("Kokoro: {err}"))),
+        }
+    }
+}
+
+impl Drop for Kokoro {
+    fn drop(&mut self) {
+        if let Some((mut child, stdin, _)) = self.process.take() {
+            drop(stdin);
+            let _ = child.wait();
+        }
+    }
+}
+
+/// `text` made safe for a file name: runs of anything but ASCII letters,
+/// digits, `-` and `_` become a single `-`.
+fn slug(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        let c = match c.is_ascii_alphanumeric() || c == '_' {
+            true => c,
+            false => '-',
+        };
+        if !(c == '-' && out.ends_with('-')) {
+            out.push(c);
+        }
+    }
+    out.trim_matches('-').to_string()
+}
+
+/// The start of `text`'s slug, for file names.
+fn file_slug(text: &str) -> String {
+    let slug: String = slug(text).chars().take(48).collect();
+    if slug.is_empty() { "tts".into() } else { slug }
+}
+
+/// File name (without extension) of the audio for `text` (pronunciation
+/// applied) in `voice`: `{slug}-{voice}-{hash}`. `tags` (the backend's
+/// [`Tts::cache_tag`]) go into the hash too; empty ones are left out, as the
+/// player's speech script had none. A dictionary edit only changes the
+/// keys of the lines whose spoken text it changes.
+pub fn cue_basename(text: &str, voice: &str, tags: &[&str]) -> String {
+    let mut key: Vec<&str> = tags.iter().copied().filter(|t| !t.is_empty()).collect();
+    key.extend([voice, text]);
+    let key = fsx::short_hash(key.join("::").as_bytes());
+    format!("{}-{voice}-{key}", file_slug(text))
+}
+
+/// A `say` cue and its audio; see [`Builder::audio`].
+#[derive(Debug, Clone)]
+pub struct CueAudio {
+    pub text: String,
+    pub src: Option<String>,
+    pub size: Option<u64>,
+}
+
+/// What a speech build did.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub generated: usize,
+    pub cached: usize,
+}
+
+/// Synthesizes the `speech` cues of compiled timelines.
+pub struct Builder<'a> {
+    project: &'a Project,
+    format: Format,
+    tts: Box<dyn Tts>,
+    dictionary: Dictionary,
+    ffmpeg: Option<PathBuf>,
+    pub report: Report,
+}
+
+impl<'a> Builder<'a> {
+    pub fn new(project: &'a Project, options: &Options) -> io::Result<Builder<'a>> {
+        Ok(Builder {
+            project,
+            format: options.format,
+            tts: options.tts(project)?,
+            dictionary: dictionary(project, options.pronunciation.as_deref())?,
+            ffmpeg: None,
+            report: Report::default(),
+        })
+    }
+
+    /// Replace the `speech` cues of compiled timeline `name` with `audio`
+    /// attachments, synthesizing audio that isn't cached yet.
+    pub fn build(&mut self, name: &str) -> io::Result<()> {
+        compile::check_name(name)?;
+        let compiled = self.project.compiled();
+        let path = compiled.join(format!("{name}.json"));
+        let json: Value = serde_json::from_slice(&fs::read(&path)?)?;
+        let Some(mut items) = json.get("items").cloned().filter(Value::is_array) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: expected {{ name, items[] }}", path.display()),
+            ));
+        };
+        let dir = self.project.public().join("speech").join(name);
+        fs::create_dir_all(&dir)?;
+        let mut result = Ok(());
+        speech_to_audio(&mut items, |cue| {
+            let voice = cue["voice"].as_str().unwrap_or_default();
+            let written = cue["text"].as_str().unwrap_or_default();
+            let text = self.dictionary.apply(written, voice);
+            let cue = Cue {
+                timeline: name,
+                id: cue["id"].as_str(),
+                text: &text,
+                written,
+                voice,
+            };
+            match self.ensure_audio(&dir, &cue) {
+                Ok(file) => Some(format!("speech/{name}/{file}")),
+                Err(e) => {
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
+                    None
+                }
+            }
+        });
+        result?;
+        compile::write_compiled(&compiled, name, &items)
+    }
+
+    /// For each `say` cue of timeline `name`: its text, its audio as
+    /// `speech/NAME/FILE` (relative to `public/`) and the size of that file
+    /// if it is there. The audio is `None` if the backend can't tell, as for
+    /// a voiceover line that isn't recorded yet.
+    pub fn audio(&self, name: &str, items: &Value) -> Vec<CueAudio> {
+        let dir = self.project.public().join("speech").join(name);
+        cues(items)
+            .map(|cue| {
+                let voice = cue["voice"].as_str().unwrap_or_default();
+                let original = cue["text"].as_str().unwrap_or_default();
+                let text = self.dictionary.apply(original, voice);
+                let cue = Cue {
+                    timeline: name,
+                    id: cue["id"].as_str(),
+                    text: &text,
+                    written: original,
+                    voice,
+                };
+                let file = self.file_name(&cue).ok();
+                let size = file
+                    .as_ref()
+                    .and_then(|f| fs::metadata(dir.join(f)).ok())
+                    .map(|m| m.len());
+                CueAudio {
+                    text: original.to_string(),
+                    src: file.map(|f| format!("speech/{name}/{f}")),
+                    size,
+                }
+            })
+            .collect()
+    }
+
+    /// `{slug}-{voice}-{hash}.{ext}` for `cue`.
+    fn file_name(&self, cue: &Cue) -> io::Result<String> {
+        let tag = self.tts.cache_tag(cue)?;
+        let basename = cue_basename(cue.text, cue.voice, &[&tag]);
+        Ok(format!("{basename}.{}", self.format.ext()))
+    }
+
+    /// The audio file (in `dir`) of `cue`, synthesized if it isn't there.
+    fn ensure_audio(&mut self, dir: &Path, cue: &Cue) -> io::Result<String> {
+        let file = self.file_name(cue)?;
+        let basename = file.rsplit_once('.').map_or(file.as_str(), |(b, _)| b);
+        let out = dir.join(&file);
+        if out.is_file() {
+            self.report.cached += 1;
+            return Ok(file);
+        }
+        let wav = dir.join(format!("{basename}.wav"));
+        if !wav.is_file() {
+            let short: String = cue.text.chars().take(42).collect();
+            let more = if cue.text.chars().count() > 42 {
+                "…"
+            } else {
+                ""
+            };
+            eprintln!(
+                "[speech:{}] TTS {} \"{short}{more}\"",
+                cue.timeline, cue.voice
+            );
+            self.tts.synthesize(cue, &wav)?;
+        }
+        if self.format != Format::Wav {
+            self.encode(&wav, &out)?;
+            fs::remove_file(&wav)?;
+        }
+        self.report.generated += 1;
+        Ok(file)
+    }
+
+    fn encode(&mut self, wav: &Path, out: &Path) -> io::Result<()> {
+        let ffmpeg = match &self.ffmpeg {
+            Some(f) => f.clone(),
+            None => {
+                // the player's ffmpeg-static, as its build script used
+                let bundled = self
+                    .project
+                    .player
+                    .join("node_modules/ffmpeg-static/ffmpeg");
+                let f = std::env::var_os("FFMPEG_PATH")
+                    .map(PathBuf::from)
+                    .or_else(|| deps::have("ffmpeg").then(|| "ffmpeg".into()))
+                    .or_else(|| bundled.is_file().then_some(bundled))
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            "ffmpeg not found (install it, set FFMPEG_PATH, run `npm install` in the player, or use --format wav)",
+                        )
+                    })?;
+                self.ffmpeg.insert(f).clone()
+            }
+        };
+        let status = Command::new(ffmpeg)
+            .args(["-y", "-loglevel", "error", "-i"])
+            .arg(wav)
+            .args(self.format.encoder_args())
+            .arg(out)
+            .status()?;
+        if !status.success() {
+            return Err(io::Error::other(format!("ffmpeg failed ({status})")));
+        }
+        Ok(())
+    }
+}
+
+/// The `say` cues of timeline items.
+fn cues(items: &Value) -> impl Iterator<Item = &Value> {
+    items
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.get("speech")?.as_array())
+        .flatten()
+}
+
+/// The `src` of every audio attachment of timeline items.
+pub fn audio_srcs(items: &Value) -> impl Iterator<Item = &str> {
+    items
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|item| match item.get("audio") {
+            Some(Value::Array(a)) => a.iter().collect(),
+            Some(a) => vec![a],
+            None => Vec::new(),
+        })
+        .filter_map(|a| a.get("src")?.as_str())
+}
+
+/// Synthesized audio in `public/speech` that isn't one of `keep`
+/// (`speech/NAME/FILE` paths; any extension of a kept file is kept). Only
+/// files named like synthesized audio, `{slug}-{voice}-{hash}.{ext}`, are
+/// candidates; clips put there by hand are left alone.
+pub fn orphans(project: &Project, keep: &BTreeSet<String>) -> io::Result<Vec<PathBuf>> {
+    let stem = |src: &str| src.rsplit_once('.').map_or(src, |(s, _)| s).to_string();
+    let keep: BTreeSet<String> = keep.iter().map(|s| stem(s)).collect();
+    let root = project.public().join("speech");
+    let mut out = Vec::new();
+    let Ok(dirs) = fs::read_dir(&root) else {
+        return Ok(out);
+    };
+    for dir in dirs {
+        let dir = dir?;
+        if !dir.file_type()?.is_dir() {
+            continue;
+        }
+        let name = dir.file_name().to_string_lossy().into_owned();
+        for file in fs::read_dir(dir.path())? {
+            let path = file?.path();
+            let file = path.file_name().unwrap_or_default().to_string_lossy();
+            if is_synthesized(&file) && !keep.contains(&stem(&format!("speech/{name}/{file}"))) {
+                out.push(path);
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// Whether `file` is named like synthesized audio.
+fn is_synthesized(file: &str) -> bool {
+    let Some((base, ext)) = file.rsplit_once('.') else {
+        return false;
+    };
+    let hash = base.rsplit_once('-').map_or("", |(_, h)| h);
+    ["opus", "ogg", "wav"].contains(&ext)
+        && hash.len() == 16
+        && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Move each item's `speech` cues to its `audio` attachments, with the
+/// audio `src` that `synthesize(cue)` returns. An item keeps its cues if any
+/// of them returns `None`.
+fn speech_to_audio(items: &mut Value, mut synthesize: impl FnMut(&Value) -> Option<String>) {
+    for item in items.as_array_mut().into_iter().flatten() {
+        let Some(Value::Array(cues)) = item.get("speech") else {
+            continue;
+        };
+        if cues.is_empty() {
+            continue;
+        }
+        let mut audio = match item.get("audio") {
+            Some(Value::Array(a)) => a.clone(),
+            Some(a @ Value::Object(_)) => vec![a.clone()],
+            _ => Vec::new(),
+        };
+        let mut complete = true;
+        for cue in cues {
+            let Some(src) = synthesize(cue) else {
+                complete = false;
+                continue;
+            };
+            let offset = cue.get("offsetSec").cloned().unwrap_or(json!(0));
+            audio.push(json!({ "src": src, "anchor": cue["anchor"], "offsetSec": offset }));
+        }
+        if let (true, Value::Object(obj)) = (complete, item) {
+            obj.remove("speech");
+            obj.insert("audio".into(), Value::Array(audio));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_keys_match_the_player_script() {
+        assert_eq!(
+            cue_basename("Hello, world!", "af_heart", &["", ""]),
+            format!(
+                "Hello-world-af_heart-{}",
+                fsx::short_hash(b"af_heart::Hello, world!")
+            )
+        );
+        assert_eq!(
+            cue_basename("Hello, world!", "af_heart", &["stub", "command:x"]),
+            format!(
+                "Hello-world-af_heart-{}",
+                fsx::short_hash(b"stub::command:x::af_heart::Hello, world!")
+            )
+        );
+        assert_eq!(
+            cue_basename("…", "af_heart", &[]).split('-').next(),
+            Some("tts")
+        );
+        assert!(is_synthesized(&format!(
+            "{}.opus",
+            cue_basename("Hi", "af_heart", &[])
+        )));
+        assert!(!is_synthesized("music.opus"));
+
+        let mut items = json!([
+            { "type": "card", "speech": [{ "text": "Hi", "voice": "v", "anchor": "inBegin" }] },
+            { "type": "cast", "audio": { "src": "music.opus" }, "speech": [
+                { "text": "A", "voice": "v", "anchor": "inEnd", "offsetSec": 1.5 }
+            ] },
+            { "type": "card", "speech": [{ "text": "fails", "voice": "v", "anchor": "inBegin" }] }
+        ]);
+        speech_to_audio(&mut items, |cue| {
+            let text = cue["text"].as_str().unwrap();
+            (text != "fails").then(|| format!("speech/t/{text}.opus"))
+        });
+        assert_eq!(
+            items,
+            json!([
+                { "type": "card", "audio": [{ "src": "speech/t/Hi.opus", "anchor": "inBegin", "offsetSec": 0 }] },
+                { "type": "cast", "audio": [
+                    { "src": "music.opus" },
+                    { "src": "speech/t/A.opus", "anchor": "inEnd", "offsetSec": 1.5 }
+                ] },
+                { "type": "card", "speech": [{ "text": "fails", "voice": "v", "anchor": "inBegin" }] }
+            ])
+        );
+    }
+
+    #[test]
+    fn build_synthesizes_once_and_rewrites_cues() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let project = Project {
+            player: root.join("player"),
+            timelines: root.join("timelines"),
+            casts: root.join("casts"),
+        };
+        let compiled = project.compiled();
+        fs::create_dir_all(&compiled).unwrap();
+        let items = json!([
+            { "type": "card", "speech": [
+                { "text": "Hello there.", "voice": "af_heart", "anchor": "inBegin" },
+                { "text": "Bye.", "voice": "af_heart", "anchor": "inEnd" }
+            ] }
+        ]);
+        let options = Options {
+            backend: Backend::Stub,
+            format: Format::Wav,
+            command: None,
+            voiceover: None,
+            pronunciation: None,
+        };
+        let mut reports = Vec::new();
+        for _ in 0..2 {
+            compile::write_compiled(&compiled, "t", &items).unwrap();
+            let mut builder = Builder::new(&project, &options).unwrap();
+            builder.build("t").unwrap();
+            reports.push((builder.report.generated, builder.report.cached));
+        }
+        assert_eq!(reports, [(2, 0), (0, 2)]);
+
+        let json: Value =
+            serde_json::from_slice(&fs::read(compiled.join("t.json")).unwrap()).unwrap();
+        let audio = &json["items"][0]["audio"];
+        assert!(json["items"][0].get("speech").is_none());
+        assert_eq!(audio[0]["anchor"], "inBegin");
+        assert_eq!(audio[1]["anchor"], "inEnd");
+        for a in audio.as_array().unwrap() {
+            let src = a["src"].as_str().unwrap();
+            assert!(
+                src.starts_with("speech/t/") && src.ends_with(".wav"),
+                "{src}"
+            );
+            assert!(project.public().join(src).is_file());
+        }
+        assert!(
+            audio[0]["src"]
+                .as_str()
+                .unwrap()
+                .starts_with("speech/t/Hello-there-af_heart-")
+        );
+    }
+
+    #[test]
+    fn orphans_are_unused_synthesized_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let project = Project {
+            player: root.join("player"),
+            timelines: root.join("timelines"),
+            casts: root.join("casts"),
+        };
+        let dir = project.public().join("speech/t");
+        fs::create_dir_all(&dir).unwrap();
+        let kept = format!("{}.opus", cue_basename("Kept", "af_heart", &[]));
+        let orphan = format!("{}.opus", cue_basename("Edited", "af_heart", &[]));
+        // a kept line's other formats are kept too
+        let kept_wav = kept.replace(".opus", ".wav");
+        for f in [kept.as_str(), &kept_wav, &orphan, "music.opus"] {
+            fs::write(dir.join(f), "").unwrap();
+        }
+        fs::write(project.public().join("speech/stray.opus"), "").unwrap();
+
+        let keep = BTreeSet::from([format!("speech/t/{kept}")]);
+        assert_eq!(orphans(&project, &keep).unwrap(), [dir.join(&orphan)]);
+    }
+
+    #[test]
+    fn command_backend_pipes_text_and_voice() {
+        let tmp = tempfile::tempdir().unwrap();
+        let wav = tmp.path().join("tts.wav");
+        let cue = Cue {
+            timeline: "t",
+            id: None,
+            text: "hello",
+            written: "hello",
+            voice: "en",
+        };
+        let mut tts = Shell {
+            command: r#"printf 'RIFF%s:' "$SPEECH_VOICE"; cat"#.into(),
+            dir: tmp.path().to_path_buf(),
+        };
+        tts.synthesize(&cue, &wav).unwrap();
+        assert_eq!(
+            fs::read_to_string(&wav).unwrap(),
+            "RIFFen:{\"text\":\"hello\",\"voice\":\"en\",\"timeline\":\"t\",\"id\":null}\n"
+        );
+        tts.command = "echo not a wav".into();
+        assert!(tts.synthesize(&cue, &wav).is_err());
+    }
+
+    #[test]
+    fn voiceover_is_found_by_the_text_as_written() {
+        let files = Files {
+            dir: PathBuf::from("vo"),
+        };
+        let cue = Cue {
+            timeline: "t",
+            id: None,
+            text: "traffic rocks",
+            written: "Traefik rocks",
+            voice: "af_heart",
+        };
+        assert_eq!(
+            files.recording(&cue),
+            Path::new("vo/t/Traefik-rocks-af_heart.wav")
+        );
+        let cue = Cue {
+            id: Some("intro"),
+            ..cue
+        };
+        assert_eq!(files.recording(&cue), Path::new("vo/t/intro.wav"));
+    }
+}
@@ -0,0 +1,141 @@
+use serde_json::{Value, json};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A `cast PATH COLSxROWS` line found in a `.timeline` file.
+#[derive(Debug, Clone)]
+pub struct CastRef {
+    pub timeline: PathBuf,
+    pub path: String,
+}
+
+/// Recursively collect `.timeline` files under `dir` (sorted).
+pub fn find_timeline_files(dir: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(d) = stack.pop() {
+        let Ok(rd) = fs::read_dir(&d) else { continue };
+        for entry in rd.flatten() {
+            let p = entry.path();
+            if p.is_dir() {
+                stack.push(p);
+            } else if p
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("timeline"))
+            {
+                out.push(p);
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+/// Parse a SceneScript `cast` line, returning the cast path.
+pub fn parse_cast_line(line: &str) -> Option<&str> {
+    if line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut parts = line.split_whitespace();
+    if !parts.next()?.eq_ignore_ascii_case("cast") {
+        return None;
+    }
+    let path = parts.next()?;
+    let (cols, rows) = parts.next()?.split_once('x')?;
+    if parts.next().is_some() || !is_digits(cols) || !is_digits(rows) {
+        return None;
+    }
+    Some(path)
+}
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// All cast references in every `.timeline` file under `dir`.
+pub fn cast_refs(dir: &Path) -> Vec<CastRef> {
+    let mut refs = Vec::new();
+    for tl in find_timeline_files(dir) {
+        let Ok(txt) = fs::read_to_string(&tl) else {
+            continue;
+        };
+        for line in txt.lines() {
+            if let Some(path) = parse_cast_line(line) {
+                refs.push(CastRef {
+                    timeline: tl.clone(),
+                    path: path.to_string(),
+                });
+            }
+        }
+    }
+    refs
+}
+
+/// True if `cast` (a file on disk) is referenced by `r`. Timelines store
+/// whatever path was typed, so references are matched by file name.
+pub fn refers_to(r: &CastRef, cast: &Path) -> bool {
+    Path::new(&r.path).file_name() == cast.file_name()
+}
+
+/// Names of the compiled timelines listed in `dir/registry.json`.
+pub fn compiled_names(dir: &Path) -> io::Result<Vec<String>> {
+    let registry: Value = serde_json::from_slice(&fs::read(dir.join("registry.json"))?)?;
+    Ok(registry["names"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|n| n.as_str().map(String::from))
+        .collect())
+}
+
+/// `registry.json` for the compiled timelines `names`. Module paths are
+/// relative to the site root, so the player can live below any base path.
+pub fn registry(names: &[String]) -> Value {
+    let mut names = names.to_vec();
+    names.sort();
+    json!({
+        "names": names,
+        "modules": names.iter().map(|n| format!("timelines/{n}.speech.js")).collect::<Vec<_>>(),
+        "titles": names.iter().map(|n| (n.clone(), json!(n))).collect::<serde_json::Map<_, _>>(),
+    })
+}
+
+/// The ES module (`NAME.speech.js`) that registers a compiled timeline with
+/// the player.
+pub fn speech_module(name: &str, items: &Value) -> String {
+    let name = json!(name);
+    let items = serde_json::to_string_pretty(items).unwrap_or_default();
+    format!(
+        r#"// AUTO-GENERATED for timeline {name} – DO NOT EDIT
+export const name = {name};
+export const timeline = {items};
+if (typeof globalThis !== "undefined") {{
+  const root = (globalThis.shellScene ??= {{}});
+  if (typeof root.registerTimeline === "function") {{
+    root.registerTimeline(name, timeline, {name});
+  }} else {{
+    (root.__timelines ??= {{}})[name] = timeline;
+    (root.__titles ??= {{}})[name] = {name};
+  }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cast_line() {
+        assert_eq!(
+            parse_cast_line("cast casts/a.cast 80x24"),
+            Some("casts/a.cast")
+        );
+        assert_eq!(parse_cast_line("CAST a.cast 80x24  "), Some("a.cast"));
+        assert_eq!(parse_cast_line("cast a.cast"), None);
+        assert_eq!(parse_cast_line("  say \"cast a.cast 80x24\""), None);
+        assert_eq!(parse_cast_line("card \"x\""), None);
+    }
+}
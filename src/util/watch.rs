@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Modification times of the files below a set of directories. Comparing two
+/// snapshots tells which files were added, changed or removed; polling keeps
+/// this portable and free of watcher limits.
+#[derive(Debug, Default, PartialEq)]
+pub struct Snapshot(BTreeMap<PathBuf, Option<SystemTime>>);
+
+impl Snapshot {
+    /// Files below each directory (recursively) whose extension is in its
+    /// list, or all files for an empty list. Missing directories are skipped.
+    pub fn take(dirs: &[(&Path, &[&str])]) -> Snapshot {
+        let mut files = BTreeMap::new();
+        for (dir, exts) in dirs {
+            walk(dir, exts, &mut files);
+        }
+        Snapshot(files)
+    }
+
+    /// Files that differ between `self` and the newer snapshot `next`.
+    pub fn changed(&self, next: &Snapshot) -> Vec<PathBuf> {
+        let mut out: Vec<PathBuf> = next
+            .0
+            .iter()
+            .filter(|(p, t)| self.0.get(*p) != Some(t))
+            .map(|(p, _)| p.clone())
+            .collect();
+        out.extend(self.0.keys().filter(|p| !next.0.contains_key(*p)).cloned());
+        out
+    }
+}
+
+fn walk(dir: &Path, exts: &[&str], files: &mut BTreeMap<PathBuf, Option<SystemTime>>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            walk(&path, exts, files);
+        } else if exts.is_empty()
+            || path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| exts.contains(&e))
+        {
+            files.insert(path, meta.modified().ok());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn changes_between_snapshots() {
+        let t = |s| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(s));
+        let before = Snapshot(
+            [("a.timeline", t(1)), ("b.timeline", t(1))]
+                .map(|(p, m)| (PathBuf::from(p), m))
+                .into(),
+        );
+        let after = Snapshot(
+            [("a.timeline", t(2)), ("c.timeline", t(1))]
+                .map(|(p, m)| (PathBuf::from(p), m))
+                .into(),
+        );
+        assert_eq!(
+            before.changed(&after),
+            ["a.timeline", "c.timeline", "b.timeline"].map(PathBuf::from)
+        );
+        assert!(after.changed(&after).is_empty());
+    }
+}